@@ -0,0 +1,104 @@
+use crate::case::RenameRule;
+use syn::{Attribute, LitStr, Path, Result, Token};
+
+#[derive(Default)]
+pub struct Container {
+    pub krate: Option<Path>,
+    pub ignore_case: bool,
+    pub ignore_separators: bool,
+    pub rename_all: Option<RenameRule>,
+    pub borrow: bool,
+    pub identifier: bool,
+}
+
+impl Container {
+    pub fn from_attrs(attrs: &[Attribute]) -> Result<Container> {
+        let mut container = Container::default();
+
+        for attr in attrs.iter().filter(|a| a.path().is_ident("keys")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("crate") {
+                    container.krate = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("rename_all") {
+                    let rule: LitStr = meta.value()?.parse()?;
+
+                    container.rename_all = match RenameRule::from_str(&rule.value()) {
+                        Some(rule) => Some(rule),
+                        None => {
+                            return Err(syn::Error::new_spanned(
+                                rule,
+                                format!(
+                                    "unknown rename rule, expected one of {}",
+                                    RenameRule::NAMES.join(", ")
+                                ),
+                            ))
+                        }
+                    };
+                } else if meta.path.is_ident("identifier") {
+                    container.identifier = true;
+                } else if meta.path.is_ident("borrow") {
+                    container.borrow = true;
+                } else if meta.path.is_ident("ignore_case") {
+                    container.ignore_case = true;
+                } else if meta.path.is_ident("ignore_separators") {
+                    container.ignore_separators = true;
+                } else {
+                    return Err(meta.error("unknown keys container attribute"));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(container)
+    }
+}
+
+#[derive(Default)]
+pub struct Variant {
+    pub rename: Option<LitStr>,
+    pub aliases: Vec<LitStr>,
+    pub deprecated: Option<Option<LitStr>>,
+    pub deprecated_aliases: Vec<LitStr>,
+    pub prefix: Option<LitStr>,
+    pub suffix: Option<LitStr>,
+    pub pattern: Option<LitStr>,
+    pub other: bool,
+}
+
+impl Variant {
+    pub fn from_attrs(attrs: &[Attribute]) -> Result<Variant> {
+        let mut variant = Variant::default();
+
+        for attr in attrs.iter().filter(|a| a.path().is_ident("keys")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    variant.rename = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("alias") {
+                    variant.aliases.push(meta.value()?.parse()?);
+                } else if meta.path.is_ident("deprecated") {
+                    variant.deprecated = match meta.input.peek(Token![=]) {
+                        true => Some(Some(meta.value()?.parse()?)),
+                        false => Some(None),
+                    };
+                } else if meta.path.is_ident("deprecated_alias") {
+                    variant.deprecated_aliases.push(meta.value()?.parse()?);
+                } else if meta.path.is_ident("prefix") {
+                    variant.prefix = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("suffix") {
+                    variant.suffix = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("pattern") {
+                    variant.pattern = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("other") {
+                    variant.other = true;
+                } else {
+                    return Err(meta.error("unknown keys variant attribute"));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(variant)
+    }
+}
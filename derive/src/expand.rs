@@ -0,0 +1,465 @@
+use crate::attr;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    ext::IdentExt, Data, DeriveInput, Error, Fields, Ident, LitByteStr, LitStr, Result, Type,
+};
+
+struct Key<'a> {
+    ident: &'a Ident,
+    name: LitStr,
+    aliases: Vec<LitStr>,
+    deprecated: Vec<(LitStr, Option<LitStr>)>,
+}
+
+pub fn derive(input: DeriveInput) -> Result<TokenStream> {
+    let container = attr::Container::from_attrs(&input.attrs)?;
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "Keys can only be derived for enums",
+            ))
+        }
+    };
+
+    if data.variants.is_empty() {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "Keys cannot be derived for empty enums",
+        ));
+    }
+
+    let mut keys = Vec::new();
+    let mut other = None;
+
+    for variant in &data.variants {
+        let attrs = attr::Variant::from_attrs(&variant.attrs)?;
+
+        if attrs.other {
+            if other.is_some() {
+                return Err(Error::new_spanned(
+                    variant,
+                    "only one variant can be marked #[keys(other)]",
+                ));
+            }
+
+            match &variant.fields {
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                    let ty = &fields.unnamed[0].ty;
+
+                    if !is_string(ty) {
+                        return Err(Error::new_spanned(
+                            ty,
+                            "#[keys(other)] variant must hold a String",
+                        ));
+                    }
+                }
+                _ => {
+                    return Err(Error::new_spanned(
+                        variant,
+                        "#[keys(other)] variant must hold a single String",
+                    ))
+                }
+            }
+
+            other = Some(&variant.ident);
+            continue;
+        }
+
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(
+                variant,
+                "Keys variants cannot have fields",
+            ));
+        }
+
+        if attrs.prefix.is_some() || attrs.suffix.is_some() || attrs.pattern.is_some() {
+            return Err(Error::new_spanned(
+                variant,
+                "prefix, suffix and pattern are only supported by derive(ParamKeys)",
+            ));
+        }
+
+        let name = attrs.rename.unwrap_or_else(|| {
+            let ident = variant.ident.unraw().to_string();
+            let name = match container.rename_all {
+                Some(rule) => rule.apply(&ident),
+                None => ident,
+            };
+
+            LitStr::new(&name, variant.ident.span())
+        });
+
+        let deprecated = match attrs.deprecated {
+            Some(replacement) => std::iter::once(&name)
+                .chain(&attrs.aliases)
+                .chain(&attrs.deprecated_aliases)
+                .map(|s| (s.clone(), replacement.clone()))
+                .collect(),
+            None => attrs
+                .deprecated_aliases
+                .iter()
+                .map(|s| (s.clone(), Some(name.clone())))
+                .collect(),
+        };
+
+        keys.push(Key {
+            ident: &variant.ident,
+            name,
+            aliases: attrs
+                .aliases
+                .into_iter()
+                .chain(attrs.deprecated_aliases)
+                .collect(),
+            deprecated,
+        });
+    }
+
+    check_strings(&container, &keys)?;
+
+    let krate = match container.krate {
+        Some(path) => quote!(#path),
+        None => quote!(::serde_types),
+    };
+    let name = &input.ident;
+
+    let idents = keys.iter().map(|k| k.ident).collect::<Vec<_>>();
+    let names = keys.iter().map(|k| &k.name).collect::<Vec<_>>();
+    let aliases = keys.iter().flat_map(|k| &k.aliases).collect::<Vec<_>>();
+    let patterns = keys.iter().map(|k| {
+        let name = &k.name;
+        let aliases = &k.aliases;
+
+        quote!(#name #( | #aliases )*)
+    });
+    let byte_patterns = keys.iter().map(|k| {
+        let strings = std::iter::once(&k.name)
+            .chain(&k.aliases)
+            .map(|s| LitByteStr::new(s.value().as_bytes(), s.span()));
+
+        quote!(#( #strings )|*)
+    });
+
+    let loose_arm = |input: &TokenStream, strings: &[&LitStr], value: TokenStream| {
+        if !container.ignore_case && !container.ignore_separators {
+            return quote!();
+        }
+
+        let ignore_case = container.ignore_case;
+        let ignore_separators = container.ignore_separators;
+
+        quote! {
+            _ if #( #krate::keys::__private::eq_loose(#input, #strings.as_bytes(), #ignore_case, #ignore_separators) )||*
+                => #value,
+        }
+    };
+    let loose = |input: TokenStream| {
+        let arms = keys.iter().map(|k| {
+            let ident = k.ident;
+            let strings = std::iter::once(&k.name)
+                .chain(&k.aliases)
+                .collect::<Vec<_>>();
+
+            loose_arm(
+                &input,
+                &strings,
+                quote!(::core::option::Option::Some(#name::#ident)),
+            )
+        });
+
+        quote!(#( #arms )*)
+    };
+    let loose_str = loose(quote!(s.as_bytes()));
+    let loose_bytes = loose(quote!(b));
+
+    let deprecated = keys.iter().flat_map(|k| &k.deprecated).collect::<Vec<_>>();
+    let has_deprecations = !deprecated.is_empty();
+    let deprecated = if deprecated.is_empty() {
+        quote!()
+    } else {
+        let arms = deprecated.iter().map(|(s, replacement)| {
+            let replacement = match replacement {
+                Some(r) => quote!(::core::option::Option::Some(#r)),
+                None => quote!(::core::option::Option::None),
+            };
+
+            quote!(#s => #replacement,)
+        });
+        let loose_arms = deprecated.iter().map(|(s, replacement)| {
+            let replacement = match replacement {
+                Some(r) => quote!(::core::option::Option::Some(#r)),
+                None => quote!(::core::option::Option::None),
+            };
+
+            loose_arm(&quote!(s.as_bytes()), &[s], replacement)
+        });
+
+        quote! {
+            fn deprecated(s: &str) -> ::core::option::Option<#krate::keys::Deprecation> {
+                let replacement = match s {
+                    #( #arms )*
+                    #( #loose_arms )*
+                    _ => return ::core::option::Option::None,
+                };
+
+                ::core::option::Option::Some(#krate::keys::Deprecation::new(s, replacement))
+            }
+        }
+    };
+
+    let indices = (0..keys.len()).collect::<Vec<_>>();
+    let count = keys.len();
+
+    let has_other = other.is_some();
+    let (from_str_other, from_bytes_other, as_str_other, index_other) = match other {
+        Some(other) => (
+            quote!(_ => ::core::option::Option::Some(#name::#other(s.to_owned())),),
+            quote!(_ => ::core::str::from_utf8(b).ok().map(|s| #name::#other(s.to_owned())),),
+            quote!(#name::#other(s) => s,),
+            quote!(#name::#other(_) => #count,),
+        ),
+        None => (
+            quote!(_ => ::core::option::Option::None,),
+            quote!(_ => ::core::option::Option::None,),
+            quote!(),
+            quote!(),
+        ),
+    };
+
+    // Equality, hashing and `Borrow<str>` must agree, so a catch-all value
+    // holding a known name equals that key.
+    let borrow = if container.borrow {
+        quote! {
+            impl ::std::cmp::PartialEq for #name {
+                fn eq(&self, other: &#name) -> bool {
+                    #krate::keys::Keys::as_str(self) == #krate::keys::Keys::as_str(other)
+                }
+            }
+
+            impl ::std::cmp::Eq for #name {}
+
+            impl ::std::hash::Hash for #name {
+                fn hash<__H>(&self, state: &mut __H)
+                where
+                    __H: ::std::hash::Hasher,
+                {
+                    ::std::hash::Hash::hash(#krate::keys::Keys::as_str(self), state)
+                }
+            }
+
+            impl ::std::borrow::Borrow<str> for #name {
+                fn borrow(&self) -> &str {
+                    #krate::keys::Keys::as_str(self)
+                }
+            }
+        }
+    } else {
+        quote!()
+    };
+
+    // Compact formats read keys back by index, as `serialize` writes them.
+    let deserialize = match container.identifier {
+        true => quote! {
+            if #krate::keys::__private::serde::de::Deserializer::is_human_readable(&d) {
+                #krate::keys::deserialize_identifier(d)
+            } else {
+                #krate::keys::deserialize(d)
+            }
+        },
+        false => quote!(#krate::keys::deserialize(d)),
+    };
+
+    Ok(quote! {
+        #borrow
+
+        #[allow(deprecated)]
+        impl #krate::keys::Keys for #name {
+            const NAMES: &'static [&'static str] = &[
+                #( #names, )*
+            ];
+
+            const ALIASES: &'static [&'static str] = &[
+                #( #aliases, )*
+            ];
+
+            const ALL: &'static [#name] = &[
+                #( #name::#idents, )*
+            ];
+
+            const HAS_OTHER: bool = #has_other;
+            const HAS_DEPRECATIONS: bool = #has_deprecations;
+
+            type Array<T> = [T; #count];
+
+            fn from_str(s: &str) -> ::core::option::Option<#name> {
+                match s {
+                    #( #patterns => ::core::option::Option::Some(#name::#idents), )*
+                    #loose_str
+                    #from_str_other
+                }
+            }
+
+            fn from_bytes(b: &[u8]) -> ::core::option::Option<#name> {
+                match b {
+                    #( #byte_patterns => ::core::option::Option::Some(#name::#idents), )*
+                    #loose_bytes
+                    #from_bytes_other
+                }
+            }
+
+            fn as_str(&self) -> &str {
+                match self {
+                    #( #name::#idents => #names, )*
+                    #as_str_other
+                }
+            }
+
+            fn from_index(i: usize) -> ::core::option::Option<#name> {
+                match i {
+                    #( #indices => ::core::option::Option::Some(#name::#idents), )*
+                    _ => ::core::option::Option::None,
+                }
+            }
+
+            fn index(&self) -> usize {
+                match self {
+                    #( #name::#idents => #indices, )*
+                    #index_other
+                }
+            }
+
+            #deprecated
+        }
+
+        impl ::std::str::FromStr for #name {
+            type Err = #krate::keys::UnknownKey;
+
+            fn from_str(s: &str) -> ::core::result::Result<#name, #krate::keys::UnknownKey> {
+                #krate::keys::__private::parse(s)
+            }
+        }
+
+        impl<'a> ::std::convert::TryFrom<&'a str> for #name {
+            type Error = #krate::keys::UnknownKey;
+
+            fn try_from(s: &'a str) -> ::core::result::Result<#name, #krate::keys::UnknownKey> {
+                ::std::str::FromStr::from_str(s)
+            }
+        }
+
+        impl ::std::convert::TryFrom<::std::string::String> for #name {
+            type Error = #krate::keys::UnknownKey;
+
+            fn try_from(s: ::std::string::String) -> ::core::result::Result<#name, #krate::keys::UnknownKey> {
+                ::std::str::FromStr::from_str(&s)
+            }
+        }
+
+        impl ::std::fmt::Display for #name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                f.write_str(#krate::keys::Keys::as_str(self))
+            }
+        }
+
+        impl ::std::convert::AsRef<str> for #name {
+            fn as_ref(&self) -> &str {
+                #krate::keys::Keys::as_str(self)
+            }
+        }
+
+        #[allow(deprecated)]
+        impl<'de> #krate::keys::__private::serde::de::Deserialize<'de> for #name {
+            fn deserialize<__D>(d: __D) -> ::core::result::Result<#name, __D::Error>
+            where
+                __D: #krate::keys::__private::serde::de::Deserializer<'de>,
+            {
+                #deserialize
+            }
+        }
+
+        #[allow(deprecated)]
+        impl #krate::keys::__private::serde::ser::Serialize for #name {
+            fn serialize<__S>(&self, s: __S) -> ::core::result::Result<__S::Ok, __S::Error>
+            where
+                __S: #krate::keys::__private::serde::ser::Serializer,
+            {
+                #krate::keys::serialize(self, s)
+            }
+        }
+    })
+}
+
+fn check_strings(container: &attr::Container, keys: &[Key]) -> Result<()> {
+    check_unique(
+        container,
+        keys.iter()
+            .flat_map(|k| std::iter::once(&k.name).chain(&k.aliases)),
+    )?;
+
+    for (s, replacement) in keys.iter().flat_map(|k| &k.deprecated) {
+        if let Some(r) = replacement {
+            if !keys.iter().any(|k| k.name.value() == r.value()) {
+                return Err(Error::new(
+                    r.span(),
+                    format!(
+                        "replacement {:?} for {:?} is not a key",
+                        r.value(),
+                        s.value()
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Rejects empty strings and strings that match an earlier one under the
+/// container's case and separator folding.
+pub fn check_unique<'a, I>(container: &attr::Container, strings: I) -> Result<()>
+where
+    I: IntoIterator<Item = &'a LitStr>,
+{
+    let fold = |s: &str| {
+        s.chars()
+            .map(|c| match c {
+                '-' if container.ignore_separators => '_',
+                c if container.ignore_case => c.to_ascii_lowercase(),
+                c => c,
+            })
+            .collect::<String>()
+    };
+    let mut seen = Vec::new();
+
+    for s in strings {
+        let value = s.value();
+
+        if value.is_empty() {
+            return Err(Error::new(s.span(), "key strings cannot be empty"));
+        }
+
+        let folded = fold(&value);
+        if seen.contains(&folded) {
+            return Err(Error::new(
+                s.span(),
+                format!("duplicate key string {value:?}"),
+            ));
+        }
+        seen.push(folded);
+    }
+
+    Ok(())
+}
+
+fn is_string(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "String" && s.arguments.is_none()),
+        _ => false,
+    }
+}
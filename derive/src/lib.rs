@@ -0,0 +1,25 @@
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod attr;
+mod case;
+mod expand;
+mod param;
+
+#[proc_macro_derive(Keys, attributes(keys))]
+pub fn derive_keys(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand::derive(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(ParamKeys, attributes(keys))]
+pub fn derive_param_keys(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    param::derive(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
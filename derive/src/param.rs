@@ -0,0 +1,230 @@
+use crate::{attr, expand};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{ext::IdentExt, Data, DeriveInput, Error, Fields, LitStr, Result};
+
+pub fn derive(input: DeriveInput) -> Result<TokenStream> {
+    let container = attr::Container::from_attrs(&input.attrs)?;
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "ParamKeys can only be derived for enums",
+            ))
+        }
+    };
+
+    if data.variants.is_empty() {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "ParamKeys cannot be derived for empty enums",
+        ));
+    }
+
+    if container.ignore_case
+        || container.ignore_separators
+        || container.borrow
+        || container.identifier
+    {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "ParamKeys only supports the crate and rename_all options",
+        ));
+    }
+
+    let name = &input.ident;
+    let mut patterns = Vec::new();
+    let mut strings = Vec::new();
+    let mut plain = Vec::new();
+    let mut params = Vec::new();
+    let mut display = Vec::new();
+    let mut affixes = Vec::new();
+
+    for variant in &data.variants {
+        let attrs = attr::Variant::from_attrs(&variant.attrs)?;
+        let ident = &variant.ident;
+
+        if attrs.other || attrs.deprecated.is_some() || !attrs.deprecated_aliases.is_empty() {
+            return Err(Error::new_spanned(
+                variant,
+                "ParamKeys variants only support rename, alias, prefix, suffix and pattern",
+            ));
+        }
+
+        let key = attrs.rename.map(|r| r.value()).unwrap_or_else(|| {
+            let ident = ident.unraw().to_string();
+
+            match container.rename_all {
+                Some(rule) => rule.apply(&ident),
+                None => ident,
+            }
+        });
+
+        match &variant.fields {
+            Fields::Unit => {
+                if attrs.prefix.is_some() || attrs.suffix.is_some() || attrs.pattern.is_some() {
+                    return Err(Error::new_spanned(
+                        variant,
+                        "prefix, suffix and pattern require a payload field",
+                    ));
+                }
+
+                let key = LitStr::new(&key, ident.span());
+                let aliases = &attrs.aliases;
+
+                plain.push(quote!(#key #( | #aliases )* => return ::core::option::Option::Some(#name::#ident),));
+                display.push(quote!(#name::#ident => f.write_str(#key),));
+                patterns.push(key.value());
+                strings.extend(std::iter::once(key).chain(attrs.aliases));
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                if let Some(alias) = attrs.aliases.first() {
+                    return Err(Error::new(
+                        alias.span(),
+                        "alias is not supported on payload variants, use prefix instead",
+                    ));
+                }
+
+                let ty = &fields.unnamed[0].ty;
+                let prefix = attrs
+                    .prefix
+                    .unwrap_or_else(|| LitStr::new(&format!("{key}:"), ident.span()));
+                let suffix = attrs
+                    .suffix
+                    .unwrap_or_else(|| LitStr::new("", ident.span()));
+
+                if prefix.value().is_empty() {
+                    return Err(Error::new(prefix.span(), "prefix cannot be empty"));
+                }
+
+                params.push(quote! {
+                    if let ::core::option::Option::Some(v) = s
+                        .strip_prefix(#prefix)
+                        .and_then(|s| s.strip_suffix(#suffix))
+                        .and_then(|s| s.parse::<#ty>().ok())
+                    {
+                        return ::core::option::Option::Some(#name::#ident(v));
+                    }
+                });
+                display.push(quote! {
+                    #name::#ident(v) => ::core::write!(f, "{}{}{}", #prefix, v, #suffix),
+                });
+                patterns.push(match attrs.pattern {
+                    Some(pattern) => pattern.value(),
+                    None => format!("{}<payload>{}", prefix.value(), suffix.value()),
+                });
+                affixes.push((prefix, suffix.value()));
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    variant,
+                    "ParamKeys variants must be unit or hold a single payload",
+                ))
+            }
+        }
+    }
+
+    expand::check_unique(&container, &strings)?;
+    check_affixes(&strings, &affixes)?;
+
+    let krate = match container.krate {
+        Some(path) => quote!(#path),
+        None => quote!(::serde_types),
+    };
+
+    Ok(quote! {
+        #[allow(deprecated)]
+        impl #krate::keys::ParamKeys for #name {
+            const PATTERNS: &'static [&'static str] = &[
+                #( #patterns, )*
+            ];
+
+            fn parse(s: &str) -> ::core::option::Option<#name> {
+                match s {
+                    #( #plain )*
+                    _ => {}
+                }
+
+                #( #params )*
+
+                ::core::option::Option::None
+            }
+        }
+
+        #[allow(deprecated)]
+        impl ::std::fmt::Display for #name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                match self {
+                    #( #display )*
+                }
+            }
+        }
+
+        impl ::std::str::FromStr for #name {
+            type Err = #krate::keys::UnknownKey;
+
+            fn from_str(s: &str) -> ::core::result::Result<#name, #krate::keys::UnknownKey> {
+                #krate::keys::parse_param(s)
+            }
+        }
+
+        impl<'de> #krate::keys::__private::serde::de::Deserialize<'de> for #name {
+            fn deserialize<__D>(d: __D) -> ::core::result::Result<#name, __D::Error>
+            where
+                __D: #krate::keys::__private::serde::de::Deserializer<'de>,
+            {
+                #krate::keys::deserialize_param(d)
+            }
+        }
+
+        impl #krate::keys::__private::serde::ser::Serialize for #name {
+            fn serialize<__S>(&self, s: __S) -> ::core::result::Result<__S::Ok, __S::Error>
+            where
+                __S: #krate::keys::__private::serde::ser::Serializer,
+            {
+                s.collect_str(self)
+            }
+        }
+    })
+}
+
+/// Rejects keys that `parse` could not tell apart, so every key displays as
+/// a string that parses back to it.
+fn check_affixes(strings: &[LitStr], affixes: &[(LitStr, String)]) -> Result<()> {
+    for (i, (prefix, _)) in affixes.iter().enumerate() {
+        let value = prefix.value();
+
+        if let Some((other, _)) = affixes[..i]
+            .iter()
+            .find(|(o, _)| value.starts_with(&o.value()) || o.value().starts_with(&value))
+        {
+            return Err(Error::new(
+                prefix.span(),
+                format!("prefix {value:?} overlaps prefix {:?}", other.value()),
+            ));
+        }
+    }
+
+    for s in strings {
+        let value = s.value();
+
+        if let Some((prefix, _)) = affixes.iter().find(|(prefix, suffix)| {
+            let prefix = prefix.value();
+
+            value.len() >= prefix.len() + suffix.len()
+                && value.starts_with(&prefix)
+                && value.ends_with(suffix.as_str())
+        }) {
+            return Err(Error::new(
+                s.span(),
+                format!(
+                    "key string {value:?} overlaps payload prefix {:?}",
+                    prefix.value()
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
@@ -1,11 +1,92 @@
-use serde::de;
+use serde::{de, ser};
 use std::{fmt, marker::PhantomData};
 
+mod deprecation;
+mod enum_map;
+mod keyed;
+mod lenient;
+mod map;
+mod param;
+mod set;
+mod tagged;
+mod unique;
+
+pub use deprecation::{collect_deprecations, set_deprecation_hook, Deprecation};
+pub use enum_map::EnumMap;
+pub use keyed::{KeyHandler, KeyedMapVisitor, UnknownKeys, ValueAccess};
+pub use lenient::{Lenient, RejectedKey};
+pub use map::KeyMap;
+pub use param::{deserialize_param, parse_param, ParamKeys};
+pub use serde_types_derive::{Keys, ParamKeys};
+pub use set::KeySet;
+pub use tagged::{deserialize_tagged, Tagged};
+pub use unique::deserialize_unique;
+
 pub trait Keys: Sized + PartialEq + 'static {
     const NAMES: &'static [&'static str];
+    const ALIASES: &'static [&'static str] = &[];
+
+    /// Every key in declaration order, matching `NAMES`.
+    const ALL: &'static [Self];
+    const COUNT: usize = Self::NAMES.len();
+
+    /// Whether the enum has a catch-all variant. Such keys are always
+    /// encoded by name, since a catch-all value has no index.
+    const HAS_OTHER: bool = false;
+
+    /// Whether `deprecated` can return `Some`. Deprecations are only looked
+    /// up when this is set.
+    const HAS_DEPRECATIONS: bool = false;
 
+    /// `[T; N]` with one slot per entry in `NAMES`.
+    type Array<T>: KeyArray<T>;
+
+    /// Looks up a key without reporting deprecations; `FromStr`, `TryFrom`
+    /// and deserialization report them.
     fn from_str(s: &str) -> Option<Self>;
-    fn as_str(&self) -> &'static str;
+    fn as_str(&self) -> &str;
+
+    fn from_bytes(b: &[u8]) -> Option<Self> {
+        std::str::from_utf8(b).ok().and_then(Self::from_str)
+    }
+
+    /// Describes `s` if it is a deprecated spelling of a key.
+    fn deprecated(_s: &str) -> Option<Deprecation> {
+        None
+    }
+
+    fn from_index(i: usize) -> Option<Self> {
+        Self::NAMES.get(i).and_then(|name| Self::from_str(name))
+    }
+
+    /// Position of the key in `ALL`, or `COUNT` for a catch-all value.
+    fn index(&self) -> usize {
+        let s = self.as_str();
+
+        Self::NAMES
+            .iter()
+            .position(|name| *name == s)
+            .unwrap_or(Self::COUNT)
+    }
+}
+
+pub trait KeyArray<T>: AsRef<[T]> + AsMut<[T]> {
+    const LEN: usize;
+
+    fn from_fn<F>(f: F) -> Self
+    where
+        F: FnMut(usize) -> T;
+}
+
+impl<T, const N: usize> KeyArray<T> for [T; N] {
+    const LEN: usize = N;
+
+    fn from_fn<F>(f: F) -> Self
+    where
+        F: FnMut(usize) -> T,
+    {
+        std::array::from_fn(f)
+    }
 }
 
 struct Visitor<K>(PhantomData<K>)
@@ -19,6 +100,41 @@ where
     Visitor::<K>(PhantomData)
 }
 
+pub fn serialize<K, S>(k: &K, s: S) -> Result<S::Ok, S::Error>
+where
+    K: Keys,
+    S: ser::Serializer,
+{
+    if s.is_human_readable() || K::HAS_OTHER {
+        return s.serialize_str(k.as_str());
+    }
+
+    match u32::try_from(k.index()) {
+        Ok(i) if k.index() < K::COUNT => s.serialize_u32(i),
+        _ => Err(ser::Error::custom(format_args!(
+            r#"key "{}" has no index"#,
+            k.as_str()
+        ))),
+    }
+}
+
+pub fn deserialize<'de, K, D>(d: D) -> Result<K, D::Error>
+where
+    K: Keys,
+    D: de::Deserializer<'de>,
+{
+    de::DeserializeSeed::deserialize(KeySeed::<K>(PhantomData), d)?.map_err(de::Error::custom)
+}
+
+/// Deserializes a key as a struct field identifier, by name or by index.
+pub fn deserialize_identifier<'de, K, D>(d: D) -> Result<K, D::Error>
+where
+    K: Keys,
+    D: de::Deserializer<'de>,
+{
+    d.deserialize_identifier(Visitor::<K>(PhantomData))
+}
+
 impl<'de, K> de::Visitor<'de> for Visitor<K>
 where
     K: Keys
@@ -26,64 +142,293 @@ where
     type Value = K;
 
     fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "one of ")?;
+        write_names(f, K::NAMES)
+    }
 
-        let mut first = true;
-        for k in K::NAMES {
-            if !first {
-                write!(f, ", ")?;
-                first = false;
-            }
+    fn visit_u64<E>(self, v: u64) -> Result<K, E>
+    where
+        E: de::Error,
+    {
+        KeySeed::<K>(PhantomData).visit_u64(v)?.map_err(E::custom)
+    }
 
-            write!(f, r#""{k}""#)?;
+    fn visit_str<E>(self, s: &str) -> Result<K, E>
+    where
+        E: de::Error,
+    {
+        KeySeed::<K>(PhantomData).visit_str(s)?.map_err(E::custom)
+    }
+
+    fn visit_bytes<E>(self, b: &[u8]) -> Result<K, E>
+    where
+        E: de::Error,
+    {
+        KeySeed::<K>(PhantomData).visit_bytes(b)?.map_err(E::custom)
+    }
+}
+
+/// Parses a key, leaving unknown names to the caller. Indices out of range
+/// and invalid UTF-8 are still errors.
+struct KeySeed<K>(PhantomData<K>);
+
+impl<'de, K> de::DeserializeSeed<'de> for KeySeed<K>
+where
+    K: Keys,
+{
+    type Value = Result<K, UnknownKey>;
+
+    fn deserialize<D>(self, d: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        if d.is_human_readable() || K::HAS_OTHER {
+            d.deserialize_str(self)
+        } else {
+            d.deserialize_u32(self)
         }
+    }
+}
 
-        Ok(())
+impl<'de, K> de::Visitor<'de> for KeySeed<K>
+where
+    K: Keys,
+{
+    type Value = Result<K, UnknownKey>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_names(f, K::NAMES)
     }
 
-    fn visit_str<E>(self, s: &str) -> Result<K, E>
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        usize::try_from(v)
+            .ok()
+            .and_then(K::from_index)
+            .map(Ok)
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        K::from_str(s).ok_or_else(|| E::unknown_field(s, K::NAMES))
+        Ok(__private::parse(s))
+    }
+
+    fn visit_bytes<E>(self, b: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match (K::from_bytes(b), std::str::from_utf8(b)) {
+            (Some(k), _) => {
+                deprecation::report_bytes::<K>(b);
+                Ok(Ok(k))
+            }
+            (None, Ok(s)) => Ok(Err(UnknownKey::new::<K>(s))),
+            (None, Err(_)) => Err(E::invalid_value(de::Unexpected::Bytes(b), &self)),
+        }
     }
 }
 
-#[macro_export]
-macro_rules! keys {
-    ($vis:vis $name:ident { $($k:ident ( $v:expr ) ,)+ }) => {
-        #[derive(Clone, PartialEq, Eq, Debug, Hash)]
-        $vis enum $name {
-            $( $k, )*
+fn write_names(f: &mut fmt::Formatter, names: &[&str]) -> fmt::Result {
+    write!(f, "one of ")?;
+
+    let mut first = true;
+    for k in names {
+        if !first {
+            write!(f, ", ")?;
         }
+        first = false;
 
-        impl $crate::keys::Keys for $name {
-            const NAMES: &'static [&'static str] = &[
-                $( $v, )*
-            ];
+        write!(f, r#""{k}""#)?;
+    }
 
-            fn from_str(s: &str) -> Option<$name> {
-                match s {
-                    $( $v => Some($name::$k), )*
-                    _ => None,
-                }
-            }
+    Ok(())
+}
 
-            fn as_str(&self) -> &'static str {
-                match self {
-                    $( $name::$k => $v, )*
-                }
-            }
+/// Error returned when a string does not name any key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownKey {
+    input: String,
+    expected: &'static [&'static str],
+}
+
+impl UnknownKey {
+    pub fn new<K>(input: &str) -> Self
+    where
+        K: Keys,
+    {
+        UnknownKey {
+            input: input.to_owned(),
+            expected: K::NAMES,
         }
+    }
 
-        impl<'de> serde::de::Deserialize<'de> for $name {
-            fn deserialize<D>(d: D) -> Result<$name, D::Error>
-            where
-                D: serde::de::Deserializer<'de>,
-            {
-                d.deserialize_str($crate::keys::visitor_for::<$name>())
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    pub fn expected(&self) -> &'static [&'static str] {
+        self.expected
+    }
+}
+
+impl fmt::Display for UnknownKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let input = &self.input;
+
+        match suggest(input, self.expected) {
+            Some(name) => write!(f, r#"unknown variant "{input}", did you mean "{name}"?"#),
+            None => {
+                write!(f, r#"unknown variant "{input}", expected "#)?;
+                write_names(f, self.expected)
             }
         }
+    }
+}
+
+impl std::error::Error for UnknownKey {}
+
+fn suggest<'a>(s: &str, names: &[&'a str]) -> Option<&'a str> {
+    names
+        .iter()
+        .map(|name| (distance(s, name), *name))
+        .filter(|(d, name)| *d <= 1.max(s.len().max(name.len()) / 3))
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, name)| name)
+}
+
+fn distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            let next = (prev + cost).min(row[j] + 1).min(row[j + 1] + 1);
+
+            prev = row[j + 1];
+            row[j + 1] = next;
+        }
+    }
+
+    row[b.len()]
+}
+
+#[doc(hidden)]
+pub mod __private {
+    use super::{deprecation, Keys, UnknownKey};
+
+    pub use serde;
+
+    /// Backs the generated `FromStr`, reporting deprecated spellings like
+    /// deserialization does.
+    pub fn parse<K>(s: &str) -> Result<K, UnknownKey>
+    where
+        K: Keys,
+    {
+        let k = K::from_str(s).ok_or_else(|| UnknownKey::new::<K>(s))?;
+
+        deprecation::report::<K>(s);
+        Ok(k)
+    }
+
+    pub fn eq_loose(a: &[u8], b: &[u8], ignore_case: bool, ignore_separators: bool) -> bool {
+        let fold = |c: u8| match c {
+            b'-' if ignore_separators => b'_',
+            c if ignore_case => c.to_ascii_lowercase(),
+            c => c,
+        };
+
+        a.len() == b.len() && a.iter().zip(b).all(|(x, y)| fold(*x) == fold(*y))
+    }
+}
+
+/// Declares an enum of keys, deriving `Keys`, `Serialize` and `Deserialize`.
+///
+/// ```
+/// serde_types::keys!(pub Color {
+///     Red("red"),
+///     Gray("gray", "grey"),
+/// });
+/// ```
+///
+/// The enum compares and hashes like its string and implements
+/// `Borrow<str>`, so maps keyed by it can be queried with a plain `&str`.
+/// A catch-all value holding a known name equals that key, though a derived
+/// `Ord` still sorts it with the catch-all values.
+///
+/// Outer attributes on the enum and on each variant are passed through, so
+/// docs, `#[cfg]` and extra derives such as `Copy` or `Ord` work as usual.
+/// A derived `Ord` sorts keys in declaration order, with catch-all values
+/// last and ordered by their string.
+///
+/// The enum also implements `FromStr`. With both `Keys` and `FromStr` in
+/// scope, `Color::from_str` is ambiguous (E0034); name the trait instead,
+/// or use `parse`:
+///
+/// ```
+/// use serde_types::keys::Keys;
+/// use std::str::FromStr;
+///
+/// serde_types::keys!(pub Color {
+///     Red("red"),
+/// });
+///
+/// assert_eq!(Some(Color::Red), <Color as Keys>::from_str("red"));
+/// assert_eq!(Ok(Color::Red), <Color as FromStr>::from_str("red"));
+/// assert_eq!(Ok(Color::Red), "red".parse::<Color>());
+/// ```
+///
+/// Every string must be unique and non-empty:
+///
+/// ```compile_fail
+/// serde_types::keys!(pub Color {
+///     Red("red"),
+///     Crimson("red"),
+/// });
+/// ```
+///
+/// ```compile_fail
+/// serde_types::keys!(pub Color {
+///     Red(""),
+/// });
+/// ```
+#[macro_export]
+macro_rules! keys {
+    (
+        $(#[$attr:meta])*
+        $vis:vis $name:ident {
+            $( $(#[$vattr:meta])* $k:ident $( ( $v:expr $(, $a:expr)* ) )? ,)+
+            _ => $other:ident $(,)?
+        }
+    ) => {
+        #[derive(::core::clone::Clone, ::core::fmt::Debug, $crate::keys::Keys)]
+        #[keys(crate = $crate, borrow)]
+        $(#[$attr])*
+        $vis enum $name {
+            $( $(#[$vattr])* #[keys($( rename = $v $(, alias = $a)* )?)] $k, )*
+            #[keys(other)] $other(::std::string::String),
+        }
+    };
+
+    (
+        $(#[$attr:meta])*
+        $vis:vis $name:ident {
+            $( $(#[$vattr:meta])* $k:ident $( ( $v:expr $(, $a:expr)* ) )? ,)+
+        }
+    ) => {
+        #[derive(::core::clone::Clone, ::core::fmt::Debug, $crate::keys::Keys)]
+        #[keys(crate = $crate, borrow)]
+        $(#[$attr])*
+        $vis enum $name {
+            $( $(#[$vattr])* #[keys($( rename = $v $(, alias = $a)* )?)] $k, )*
+        }
     };
 }
 
@@ -95,14 +440,121 @@ mod tests {
         Red("red"),
         Green("green"),
         Blue("blue"),
+        Gray("gray", "grey"),
     });
 
+    #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Keys)]
+    #[keys(crate = crate)]
+    pub enum Size {
+        /// Fits in a pocket.
+        #[keys(rename = "small")]
+        Small,
+        #[keys(rename = "large", alias = "big")]
+        Large,
+        Huge,
+    }
+
+    #[derive(Clone, PartialEq, Eq, Debug, Hash, Keys)]
+    #[keys(crate = crate, ignore_case, ignore_separators)]
+    pub enum Tone {
+        #[keys(rename = "light_red")]
+        LightRed,
+        #[keys(rename = "dark_blue", alias = "navy")]
+        DarkBlue,
+    }
+
+    keys!(
+        #[keys(rename_all = "kebab-case")]
+        pub Mode {
+            DarkBlue,
+            LightRed("light", "lite"),
+            HTTPProxy,
+        }
+    );
+
+    keys!(
+        /// Keys with variant attributes.
+        #[derive(Copy, PartialOrd, Ord)]
+        pub Platform {
+            /// Linux and friends.
+            Unix("unix"),
+            #[deprecated]
+            Dos("dos"),
+            #[cfg(any())]
+            Amiga("amiga"),
+            Windows("windows"),
+        }
+    );
+
+    #[allow(dead_code, non_camel_case_types)]
+    mod shadowed {
+        mod serde {}
+        type Result<T> = std::result::Result<T, ()>;
+        type Option<T> = std::result::Result<T, ()>;
+        type String = ();
+        struct Some;
+
+        crate::keys!(pub D {
+            Only("only"),
+            _ => Other,
+        });
+
+        #[derive(Debug, crate::keys::Keys)]
+        #[keys(crate = crate, borrow)]
+        pub enum H {
+            A,
+        }
+
+        #[derive(Debug, PartialEq, crate::keys::ParamKeys)]
+        #[keys(crate = crate)]
+        pub enum S {
+            Plain,
+            Retry(u32),
+        }
+
+        #[test]
+        fn generated_code_is_hygienic() {
+            let k: D = serde_json::from_str("\"only\"").unwrap();
+            assert_eq!(serde_json::to_string(&k).unwrap(), "\"only\"");
+            assert_eq!(D::Other("x".into()), "x".parse::<D>().unwrap());
+
+            assert_eq!("\"A\"", serde_json::to_string(&H::A).unwrap());
+            assert_eq!(S::Retry(2), serde_json::from_str("\"Retry:2\"").unwrap());
+            assert_eq!("\"Plain\"", serde_json::to_string(&S::Plain).unwrap());
+        }
+    }
+
+    keys!(pub Shape {
+        Circle("circle"),
+        Square("square"),
+        _ => Other,
+    });
+
+    keys!(
+        #[derive(PartialOrd, Ord)]
+        pub Priority {
+            Low("low"),
+            High("high"),
+            Critical("critical"),
+            _ => Other,
+        }
+    );
+
     #[test]
     fn from_str() {
         assert_eq!(Some(Color::Blue), Color::from_str("blue"));
         assert_eq!(None, Color::from_str("purple"));
     }
 
+    #[test]
+    fn from_str_alias() {
+        assert_eq!(Some(Color::Gray), Color::from_str("gray"));
+        assert_eq!(Some(Color::Gray), Color::from_str("grey"));
+        assert_eq!("gray", Color::Gray.as_str());
+        assert_eq!(&["red", "green", "blue", "gray"], Color::NAMES);
+        assert_eq!(&["grey"], Color::ALIASES);
+    }
+
     #[test]
     fn to_str() {
         assert_eq!("blue", Color::Blue.as_str());
@@ -125,4 +577,320 @@ mod tests {
         assert_eq!(Some(&100), data.get(&Color::Red));
         assert_eq!(Some(&200), data.get(&Color::Green));
     }
+
+    #[test]
+    fn borrows_str() {
+        use std::collections::HashMap;
+
+        let json = serde_json::json!({ "blue": 0, "grey": 100 });
+        let data: HashMap<Color, u8> = serde_json::from_value(json).unwrap();
+
+        assert_eq!(Some(&0), data.get("blue"));
+        assert_eq!(Some(&100), data.get("gray"));
+        assert_eq!(None, data.get("grey"));
+
+        let data: HashMap<Shape, u8> = [(Shape::Other("hexagon".into()), 6)].into();
+        assert_eq!(Some(&6), data.get("hexagon"));
+
+        assert_eq!(Shape::Circle, Shape::Other("circle".into()));
+
+        let data: HashMap<Shape, u8> = [(Shape::Other("circle".into()), 1)].into();
+        assert_eq!(Some(&1), data.get("circle"));
+        assert_eq!(Some(&1), data.get(&Shape::Circle));
+    }
+
+    #[test]
+    fn serializes() {
+        assert_eq!(
+            serde_json::json!("blue"),
+            serde_json::to_value(Color::Blue).unwrap()
+        );
+    }
+
+    #[test]
+    fn serializes_hashmap() {
+        use std::collections::HashMap;
+
+        let json = serde_json::json!({ "blue": 0, "red": 100, "green": 200, });
+        let data: HashMap<Color, u8> = serde_json::from_value(json.clone()).unwrap();
+
+        assert_eq!(json, serde_json::to_value(&data).unwrap());
+    }
+
+    #[test]
+    fn from_str_other() {
+        assert_eq!(Some(Shape::Circle), Shape::from_str("circle"));
+        assert_eq!(
+            Some(Shape::Other("hexagon".into())),
+            Shape::from_str("hexagon")
+        );
+        assert_eq!("hexagon", Shape::Other("hexagon".into()).as_str());
+        assert_eq!(&["circle", "square"], Shape::NAMES);
+    }
+
+    #[test]
+    fn deserializes_hashmap_other() {
+        use std::collections::HashMap;
+
+        let json = serde_json::json!({ "circle": 1, "hexagon": 6, });
+        let data: HashMap<Shape, u8> = serde_json::from_value(json.clone()).unwrap();
+
+        assert_eq!(Some(&1), data.get(&Shape::Circle));
+        assert_eq!(Some(&6), data.get(&Shape::Other("hexagon".into())));
+        assert_eq!(json, serde_json::to_value(&data).unwrap());
+    }
+
+    #[test]
+    fn derived() {
+        assert_eq!(&["small", "large", "Huge"], Size::NAMES);
+        assert_eq!(Some(Size::Large), Size::from_str("big"));
+        assert_eq!("small", Size::Small.as_str());
+
+        let json = serde_json::json!(["small", "big", "Huge"]);
+        let data: Vec<Size> = serde_json::from_value(json).unwrap();
+
+        assert_eq!(vec![Size::Small, Size::Large, Size::Huge], data);
+        assert_eq!(
+            serde_json::json!(["small", "large", "Huge"]),
+            serde_json::to_value(&data).unwrap()
+        );
+    }
+
+    #[test]
+    fn from_str_loose() {
+        assert_eq!(Some(Tone::LightRed), Tone::from_str("light_red"));
+        assert_eq!(Some(Tone::LightRed), Tone::from_str("LIGHT_RED"));
+        assert_eq!(Some(Tone::DarkBlue), Tone::from_str("Dark-Blue"));
+        assert_eq!(Some(Tone::DarkBlue), Tone::from_str("NAVY"));
+        assert_eq!(None, Tone::from_str("darkblue"));
+        assert_eq!("dark_blue", Tone::DarkBlue.as_str());
+        assert_eq!(None, Color::from_str("RED"));
+    }
+
+    #[test]
+    fn unknown_key_errors() {
+        let err = serde_json::from_value::<Color>(serde_json::json!("gren")).unwrap_err();
+        assert_eq!(
+            r#"unknown variant "gren", did you mean "green"?"#,
+            err.to_string()
+        );
+
+        let err = serde_json::from_value::<Color>(serde_json::json!("purple")).unwrap_err();
+        assert_eq!(
+            r#"unknown variant "purple", expected one of "red", "green", "blue", "gray""#,
+            err.to_string()
+        );
+
+        let err = serde_json::from_value::<Color>(serde_json::json!(true)).unwrap_err();
+        assert_eq!(
+            r#"invalid type: boolean `true`, expected one of "red", "green", "blue", "gray""#,
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn index() {
+        assert_eq!(2, Color::Blue.index());
+        assert_eq!(Some(Color::Blue), Color::from_index(2));
+        assert_eq!(None, Color::from_index(4));
+        assert_eq!(2, Shape::Other("hexagon".into()).index());
+    }
+
+    #[test]
+    fn compact_roundtrip() {
+        use std::collections::HashMap;
+
+        assert_eq!(
+            2u32.to_le_bytes().to_vec(),
+            bincode::serialize(&Color::Blue).unwrap()
+        );
+
+        let data = HashMap::from([(Color::Blue, 0u8), (Color::Gray, 100)]);
+        let bytes = bincode::serialize(&data).unwrap();
+
+        assert_eq!(
+            data,
+            bincode::deserialize::<HashMap<Color, u8>>(&bytes).unwrap()
+        );
+
+        let shapes = vec![Shape::Square, Shape::Other("hexagon".into())];
+        let bytes = bincode::serialize(&shapes).unwrap();
+
+        assert_eq!(shapes, bincode::deserialize::<Vec<Shape>>(&bytes).unwrap());
+        assert!(bincode::deserialize::<Color>(&7u32.to_le_bytes()).is_err());
+    }
+
+    #[test]
+    fn from_bytes() {
+        use serde::de::{value::BytesDeserializer, value::Error, Deserializer};
+
+        assert_eq!(Some(Color::Gray), Color::from_bytes(b"grey"));
+        assert_eq!(None, Color::from_bytes(b"\xffred"));
+        assert_eq!(Some(Tone::DarkBlue), Tone::from_bytes(b"DARK-BLUE"));
+        assert_eq!(
+            Some(Shape::Other("hexagon".into())),
+            Shape::from_bytes(b"hexagon")
+        );
+
+        let d = BytesDeserializer::<Error>::new(b"blue");
+        assert_eq!(
+            Color::Blue,
+            d.deserialize_bytes(super::visitor_for::<Color>()).unwrap()
+        );
+    }
+
+    #[test]
+    fn rename_all() {
+        assert_eq!(&["dark-blue", "light", "h-t-t-p-proxy"], Mode::NAMES);
+        assert_eq!(Some(Mode::LightRed), Mode::from_str("lite"));
+        assert_eq!("dark-blue", Mode::DarkBlue.as_str());
+    }
+
+    #[test]
+    fn raw_identifiers() {
+        #[derive(Clone, PartialEq, Eq, Debug, Keys)]
+        #[keys(crate = crate, rename_all = "snake_case")]
+        enum Token {
+            r#Type,
+            r#MatchArm,
+        }
+
+        assert_eq!(&["type", "match_arm"], Token::NAMES);
+        assert_eq!(Some(Token::r#Type), Token::from_str("type"));
+    }
+
+    #[test]
+    fn attributes() {
+        assert_eq!(&["unix", "dos", "windows"], Platform::NAMES);
+        assert_eq!(2, Platform::Windows.index());
+        assert!(Platform::Unix < Platform::Windows);
+
+        let p = Platform::Unix;
+        let copy = p;
+        assert_eq!(p, copy);
+    }
+
+    #[test]
+    fn all() {
+        assert_eq!(4, Color::COUNT);
+        assert_eq!(
+            &[Color::Red, Color::Green, Color::Blue, Color::Gray],
+            Color::ALL
+        );
+        assert!(Color::ALL
+            .iter()
+            .enumerate()
+            .all(|(i, c)| c.index() == i && Color::from_index(i).as_ref() == Some(c)));
+        assert_eq!(&[Shape::Circle, Shape::Square], Shape::ALL);
+    }
+
+    #[test]
+    fn declaration_order() {
+        use std::collections::BTreeMap;
+
+        let json = r#"{"critical":3,"zzz":5,"low":1,"high":2,"aaa":4}"#;
+        let data: BTreeMap<Priority, u8> = serde_json::from_str(json).unwrap();
+
+        assert!(Priority::Low < Priority::Critical);
+        assert!(Priority::Critical < Priority::Other("aaa".into()));
+        assert_eq!(
+            r#"{"low":1,"high":2,"critical":3,"aaa":4,"zzz":5}"#,
+            serde_json::to_string(&data).unwrap()
+        );
+    }
+
+    #[test]
+    fn std_traits() {
+        use super::UnknownKey;
+
+        assert_eq!(Ok(Color::Gray), "grey".parse::<Color>());
+        assert_eq!(Ok(Color::Red), Color::try_from("red"));
+        assert_eq!(Ok(Color::Red), Color::try_from(String::from("red")));
+        assert_eq!("blue", format!("{}", Color::Blue));
+        assert_eq!("blue", Color::Blue.as_ref());
+
+        let err = "purple".parse::<Color>().unwrap_err();
+        assert_eq!(UnknownKey::new::<Color>("purple"), err);
+        assert_eq!("purple", err.input());
+        assert_eq!(Color::NAMES, err.expected());
+    }
+
+    #[test]
+    fn from_str_with_both_traits() {
+        use std::str::FromStr;
+
+        assert_eq!(Some(Color::Gray), <Color as Keys>::from_str("grey"));
+        assert_eq!(Ok(Color::Gray), <Color as FromStr>::from_str("grey"));
+        assert_eq!(Ok(Color::Gray), "grey".parse::<Color>());
+    }
+
+    #[test]
+    fn identifier() {
+        use serde::de::{self, IntoDeserializer};
+        use std::fmt;
+
+        keys!(
+            #[keys(identifier)]
+            Field {
+                X("x"),
+                Y("y"),
+            }
+        );
+
+        #[derive(Debug, PartialEq)]
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        impl<'de> de::Deserialize<'de> for Point {
+            fn deserialize<D>(d: D) -> Result<Point, D::Error>
+            where
+                D: de::Deserializer<'de>,
+            {
+                struct Visitor;
+
+                impl<'de> de::Visitor<'de> for Visitor {
+                    type Value = Point;
+
+                    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                        write!(f, "a point")
+                    }
+
+                    fn visit_map<A>(self, mut access: A) -> Result<Point, A::Error>
+                    where
+                        A: de::MapAccess<'de>,
+                    {
+                        let (mut x, mut y) = (None, None);
+
+                        while let Some(field) = access.next_key::<Field>()? {
+                            match field {
+                                Field::X => x = Some(access.next_value()?),
+                                Field::Y => y = Some(access.next_value()?),
+                            }
+                        }
+
+                        Ok(Point {
+                            x: x.ok_or_else(|| de::Error::missing_field("x"))?,
+                            y: y.ok_or_else(|| de::Error::missing_field("y"))?,
+                        })
+                    }
+                }
+
+                d.deserialize_struct("Point", Field::NAMES, Visitor)
+            }
+        }
+
+        let point: Point = serde_json::from_str(r#"{ "y": 2, "x": 1 }"#).unwrap();
+        assert_eq!(Point { x: 1, y: 2 }, point);
+
+        let d: de::value::U64Deserializer<de::value::Error> = 1u64.into_deserializer();
+        assert_eq!(Field::Y, super::deserialize_identifier(d).unwrap());
+
+        let bytes = bincode::serialize(&[Field::Y, Field::X]).unwrap();
+        assert_eq!(
+            [Field::Y, Field::X],
+            bincode::deserialize::<[Field; 2]>(&bytes).unwrap()
+        );
+    }
 }
@@ -0,0 +1,199 @@
+use super::Keys;
+use std::{cell::RefCell, fmt, sync::RwLock};
+
+static HOOK: RwLock<Option<fn(&Deprecation)>> = RwLock::new(None);
+
+thread_local! {
+    static COLLECTED: RefCell<Vec<Vec<Deprecation>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A deprecated key string that was accepted while deserializing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Deprecation {
+    input: String,
+    replacement: Option<&'static str>,
+}
+
+impl Deprecation {
+    pub fn new(input: &str, replacement: Option<&'static str>) -> Self {
+        Deprecation {
+            input: input.to_owned(),
+            replacement,
+        }
+    }
+
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    pub fn replacement(&self) -> Option<&'static str> {
+        self.replacement
+    }
+}
+
+impl fmt::Display for Deprecation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, r#"key "{}" is deprecated"#, self.input)?;
+
+        match self.replacement {
+            Some(r) => write!(f, r#", use "{r}" instead"#),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Installs a process-wide callback run for every deprecated key
+/// deserialized or parsed, or removes it with `None`.
+pub fn set_deprecation_hook(hook: Option<fn(&Deprecation)>) {
+    *HOOK.write().unwrap_or_else(|e| e.into_inner()) = hook;
+}
+
+/// Runs `f` and returns the deprecated keys it deserialized or parsed on
+/// this thread.
+pub fn collect_deprecations<T, F>(f: F) -> (T, Vec<Deprecation>)
+where
+    F: FnOnce() -> T,
+{
+    struct Scope;
+
+    impl Drop for Scope {
+        fn drop(&mut self) {
+            COLLECTED.with(|c| c.borrow_mut().pop());
+        }
+    }
+
+    COLLECTED.with(|c| c.borrow_mut().push(Vec::new()));
+    let scope = Scope;
+    let value = f();
+    let collected = COLLECTED.with(|c| c.borrow_mut().last_mut().map(std::mem::take));
+    drop(scope);
+
+    (value, collected.unwrap_or_default())
+}
+
+pub(super) fn report<K>(s: &str)
+where
+    K: Keys,
+{
+    if !K::HAS_DEPRECATIONS {
+        return;
+    }
+
+    let deprecation = match K::deprecated(s) {
+        Some(deprecation) => deprecation,
+        None => return,
+    };
+
+    if let Some(hook) = *HOOK.read().unwrap_or_else(|e| e.into_inner()) {
+        hook(&deprecation);
+    }
+
+    COLLECTED.with(|c| {
+        if let Some(collected) = c.borrow_mut().last_mut() {
+            collected.push(deprecation);
+        }
+    });
+}
+
+pub(super) fn report_bytes<K>(b: &[u8])
+where
+    K: Keys,
+{
+    if !K::HAS_DEPRECATIONS {
+        return;
+    }
+
+    if let Ok(s) = std::str::from_utf8(b) {
+        report::<K>(s);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect_deprecations, set_deprecation_hook, Deprecation};
+    use crate::keys::Keys;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    crate::keys!(Grade {
+        Pass("pass"),
+    });
+
+    crate::keys!(
+        #[keys(ignore_case)]
+        Color {
+            Gray("gray", "grey"),
+            #[keys(deprecated_alias = "colour")]
+            Custom("custom"),
+            #[keys(deprecated = "gray")]
+            Silver("silver"),
+            #[keys(deprecated)]
+            Sepia("sepia"),
+        }
+    );
+
+    #[test]
+    fn deprecated() {
+        assert_eq!(
+            (false, true),
+            (Grade::HAS_DEPRECATIONS, Color::HAS_DEPRECATIONS)
+        );
+        assert_eq!(None, Color::deprecated("grey"));
+        assert_eq!(
+            Some(Deprecation::new("Colour", Some("custom"))),
+            Color::deprecated("Colour")
+        );
+        assert_eq!(
+            r#"key "silver" is deprecated, use "gray" instead"#,
+            Color::deprecated("silver").unwrap().to_string()
+        );
+        assert_eq!(
+            Some(None),
+            Color::deprecated("sepia").map(|d| d.replacement())
+        );
+    }
+
+    #[test]
+    fn collects() {
+        let json = r#"["gray", "silver", "colour", "sepia"]"#;
+        let (colors, deprecations) =
+            collect_deprecations(|| serde_json::from_str::<Vec<Color>>(json).unwrap());
+
+        assert_eq!(
+            vec![Color::Gray, Color::Silver, Color::Custom, Color::Sepia],
+            colors
+        );
+        assert_eq!(
+            vec!["silver", "colour", "sepia"],
+            deprecations.iter().map(|d| d.input()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn collects_from_parse() {
+        let (color, deprecations) = collect_deprecations(|| {
+            let _ = Color::from_str("colour");
+            "silver".parse::<Color>()
+        });
+
+        assert_eq!(Ok(Color::Silver), color);
+        assert_eq!(vec![Deprecation::new("silver", Some("gray"))], deprecations);
+    }
+
+    #[test]
+    fn hook() {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        // Other tests deserialize deprecated keys concurrently, so only
+        // count the spelling this test uses.
+        set_deprecation_hook(Some(|d| {
+            if d.input() == "SILVER" {
+                CALLS.fetch_add(1, Ordering::SeqCst);
+            }
+        }));
+        serde_json::from_str::<Color>(r#""SILVER""#).unwrap();
+        set_deprecation_hook(None);
+        serde_json::from_str::<Color>(r#""SILVER""#).unwrap();
+
+        assert_eq!(1, CALLS.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,229 @@
+use super::{KeyArray, Keys};
+use serde::{de, ser};
+use std::{fmt, marker::PhantomData, ops};
+
+/// Map holding a value for every key, so lookups cannot fail.
+pub struct EnumMap<K, V>
+where
+    K: Keys,
+{
+    values: K::Array<V>,
+}
+
+impl<K, V> EnumMap<K, V>
+where
+    K: Keys,
+{
+    const SIZED: () = assert!(
+        <K::Array<V> as KeyArray<V>>::LEN == K::COUNT,
+        "Keys::Array must have one slot per key"
+    );
+
+    pub fn from_fn<F>(mut f: F) -> Self
+    where
+        F: FnMut(K) -> V,
+    {
+        let () = Self::SIZED;
+
+        EnumMap {
+            values: KeyArray::from_fn(|i| f(K::from_index(i).unwrap())),
+        }
+    }
+
+    /// Iterates over the entries in declaration order.
+    pub fn iter(&self) -> impl Iterator<Item = (K, &V)> + '_ {
+        self.values
+            .as_ref()
+            .iter()
+            .enumerate()
+            .filter_map(|(i, v)| Some((K::from_index(i)?, v)))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+        self.values.as_ref().iter()
+    }
+}
+
+impl<K, V> ops::Index<K> for EnumMap<K, V>
+where
+    K: Keys,
+{
+    type Output = V;
+
+    /// Panics if `k` is a catch-all value, which has no slot.
+    fn index(&self, k: K) -> &V {
+        &self.values.as_ref()[k.index()]
+    }
+}
+
+impl<K, V> ops::IndexMut<K> for EnumMap<K, V>
+where
+    K: Keys,
+{
+    fn index_mut(&mut self, k: K) -> &mut V {
+        &mut self.values.as_mut()[k.index()]
+    }
+}
+
+impl<K, V> Clone for EnumMap<K, V>
+where
+    K: Keys,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        EnumMap {
+            values: KeyArray::from_fn(|i| self.values.as_ref()[i].clone()),
+        }
+    }
+}
+
+impl<K, V> PartialEq for EnumMap<K, V>
+where
+    K: Keys,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.values.as_ref() == other.values.as_ref()
+    }
+}
+
+impl<K, V> fmt::Debug for EnumMap<K, V>
+where
+    K: Keys,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map()
+            .entries(K::NAMES.iter().zip(self.values.as_ref()))
+            .finish()
+    }
+}
+
+impl<K, V> ser::Serialize for EnumMap<K, V>
+where
+    K: Keys + ser::Serialize,
+    V: ser::Serialize,
+{
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        use ser::SerializeMap;
+
+        let mut map = s.serialize_map(Some(K::COUNT))?;
+        for (k, v) in self.iter() {
+            map.serialize_entry(&k, v)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de, K, V> de::Deserialize<'de> for EnumMap<K, V>
+where
+    K: Keys + de::Deserialize<'de>,
+    V: de::Deserialize<'de>,
+{
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        d.deserialize_map(Visitor(PhantomData))
+    }
+}
+
+struct Visitor<K, V>(PhantomData<(K, V)>);
+
+impl<'de, K, V> de::Visitor<'de> for Visitor<K, V>
+where
+    K: Keys + de::Deserialize<'de>,
+    V: de::Deserialize<'de>,
+{
+    type Value = EnumMap<K, V>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a map with every key")
+    }
+
+    fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut slots = K::Array::<Option<V>>::from_fn(|_| None);
+
+        while let Some(k) = access.next_key::<K>()? {
+            match slots.as_mut().get_mut(k.index()) {
+                Some(Some(_)) => {
+                    return Err(de::Error::custom(format_args!(
+                        r#"duplicate key "{}""#,
+                        k.as_str()
+                    )))
+                }
+                Some(slot) => *slot = Some(access.next_value()?),
+                None => {
+                    return Err(de::Error::custom(format_args!(
+                        r#"key "{}" has no slot in EnumMap"#,
+                        k.as_str()
+                    )))
+                }
+            }
+        }
+
+        let missing = K::NAMES
+            .iter()
+            .zip(slots.as_ref())
+            .filter(|(_, slot)| slot.is_none())
+            .map(|(name, _)| format!(r#""{name}""#))
+            .collect::<Vec<_>>();
+
+        if !missing.is_empty() {
+            return Err(de::Error::custom(format_args!(
+                "missing keys {}",
+                missing.join(", ")
+            )));
+        }
+
+        let slots = slots.as_mut();
+        Ok(EnumMap::from_fn(|k: K| slots[k.index()].take().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EnumMap;
+
+    crate::keys!(Tier {
+        Free("free"),
+        Pro("pro"),
+        Enterprise("enterprise"),
+    });
+
+    #[test]
+    fn deserializes() {
+        let json = serde_json::json!({ "pro": 10, "free": 0, "enterprise": 100 });
+        let mut map: EnumMap<Tier, u8> = serde_json::from_value(json).unwrap();
+
+        assert_eq!(10, map[Tier::Pro]);
+        map[Tier::Free] = 1;
+        assert_eq!(vec![&1, &10, &100], map.values().collect::<Vec<_>>());
+        assert_eq!(
+            serde_json::json!({ "free": 1, "pro": 10, "enterprise": 100 }),
+            serde_json::to_value(&map).unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_missing_keys() {
+        let json = serde_json::json!({ "pro": 10 });
+        let err = serde_json::from_value::<EnumMap<Tier, u8>>(json).unwrap_err();
+
+        assert_eq!(r#"missing keys "free", "enterprise""#, err.to_string());
+    }
+
+    #[test]
+    fn rejects_duplicate_keys() {
+        let json = r#"{ "free": 0, "pro": 10, "free": 1, "enterprise": 100 }"#;
+        let err = serde_json::from_str::<EnumMap<Tier, u8>>(json).unwrap_err();
+
+        assert!(err.to_string().starts_with(r#"duplicate key "free""#));
+    }
+}
@@ -0,0 +1,249 @@
+use super::{KeySeed, Keys};
+use serde::de;
+use std::{fmt, marker::PhantomData};
+
+/// Receives each entry of a map walked by [`KeyedMapVisitor`].
+///
+/// A value the handler leaves unread is skipped.
+pub trait KeyHandler<'de, K> {
+    fn handle<A>(&mut self, key: K, value: &mut ValueAccess<'_, A>) -> Result<(), A::Error>
+    where
+        A: de::MapAccess<'de>;
+}
+
+/// Gives a [`KeyHandler`] the value of the current entry, and nothing else.
+pub struct ValueAccess<'a, A> {
+    access: &'a mut A,
+    consumed: bool,
+}
+
+impl<A> ValueAccess<'_, A> {
+    pub fn next_value<'de, V>(&mut self) -> Result<V, A::Error>
+    where
+        A: de::MapAccess<'de>,
+        V: de::Deserialize<'de>,
+    {
+        self.next_value_seed(PhantomData)
+    }
+
+    /// Fails if the value was already read.
+    pub fn next_value_seed<'de, T>(&mut self, seed: T) -> Result<T::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+        T: de::DeserializeSeed<'de>,
+    {
+        if self.consumed {
+            return Err(de::Error::custom("map value was already read"));
+        }
+
+        self.consumed = true;
+        self.access.next_value_seed(seed)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnknownKeys {
+    Ignore,
+    Fail,
+}
+
+/// Visits a map entry by entry, without collecting it, and returns the
+/// handler once the map is exhausted.
+pub struct KeyedMapVisitor<K, H> {
+    handler: H,
+    unknown: UnknownKeys,
+    _keys: PhantomData<K>,
+}
+
+impl<K, H> KeyedMapVisitor<K, H> {
+    pub fn new(handler: H, unknown: UnknownKeys) -> Self {
+        KeyedMapVisitor {
+            handler,
+            unknown,
+            _keys: PhantomData,
+        }
+    }
+}
+
+impl<'de, K, H> de::Visitor<'de> for KeyedMapVisitor<K, H>
+where
+    K: Keys,
+    H: KeyHandler<'de, K>,
+{
+    type Value = H;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a map")
+    }
+
+    fn visit_map<A>(mut self, mut access: A) -> Result<H, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        while let Some(key) = access.next_key_seed(KeySeed::<K>(PhantomData))? {
+            match key {
+                Ok(k) => {
+                    let mut value = ValueAccess {
+                        access: &mut access,
+                        consumed: false,
+                    };
+
+                    self.handler.handle(k, &mut value)?;
+
+                    if !value.consumed {
+                        access.next_value::<de::IgnoredAny>()?;
+                    }
+                }
+                Err(_) if self.unknown == UnknownKeys::Ignore => {
+                    access.next_value::<de::IgnoredAny>()?;
+                }
+                Err(unknown) => return Err(de::Error::custom(unknown)),
+            }
+        }
+
+        Ok(self.handler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KeyHandler, KeyedMapVisitor, UnknownKeys, ValueAccess};
+    use bincode::Options;
+    use serde::de::{self, Deserializer};
+
+    crate::keys!(Metric {
+        Cpu("cpu"),
+        Memory("memory"),
+    });
+
+    #[derive(Default)]
+    struct Totals {
+        cpu: u64,
+        memory: u64,
+    }
+
+    impl<'de> KeyHandler<'de, Metric> for Totals {
+        fn handle<A>(&mut self, key: Metric, value: &mut ValueAccess<'_, A>) -> Result<(), A::Error>
+        where
+            A: de::MapAccess<'de>,
+        {
+            let samples = value.next_value::<Vec<u64>>()?;
+
+            match key {
+                Metric::Cpu => self.cpu += samples.iter().sum::<u64>(),
+                Metric::Memory => self.memory += samples.iter().sum::<u64>(),
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn visits_entries() {
+        let json = r#"{ "cpu": [1, 2], "disk": [9], "memory": [3], "cpu": [4] }"#;
+
+        let mut d = serde_json::Deserializer::from_str(json);
+        let totals = d
+            .deserialize_map(KeyedMapVisitor::new(Totals::default(), UnknownKeys::Ignore))
+            .unwrap();
+        assert_eq!((7, 3), (totals.cpu, totals.memory));
+
+        let mut d = serde_json::Deserializer::from_str(json);
+        let err = d
+            .deserialize_map(KeyedMapVisitor::new(Totals::default(), UnknownKeys::Fail))
+            .err()
+            .unwrap();
+        assert!(err.to_string().starts_with(r#"unknown variant "disk""#));
+    }
+
+    #[test]
+    fn rejects_unknown_index() {
+        use std::collections::BTreeMap;
+
+        let bytes = bincode::serialize(&BTreeMap::from([(7u32, vec![1u64])])).unwrap();
+
+        let mut d = bincode::Deserializer::from_slice(
+            &bytes,
+            bincode::DefaultOptions::new().with_fixint_encoding(),
+        );
+        let err = d
+            .deserialize_map(KeyedMapVisitor::new(Totals::default(), UnknownKeys::Ignore))
+            .err()
+            .unwrap();
+        assert_eq!(
+            r#"invalid value: integer `7`, expected one of "cpu", "memory""#,
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn skips_unread_values() {
+        struct CpuOnly(u64);
+
+        impl<'de> KeyHandler<'de, Metric> for CpuOnly {
+            fn handle<A>(
+                &mut self,
+                key: Metric,
+                value: &mut ValueAccess<'_, A>,
+            ) -> Result<(), A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                if key == Metric::Cpu {
+                    self.0 += value.next_value::<u64>()?;
+                }
+
+                Ok(())
+            }
+        }
+
+        let json = r#"{ "memory": { "used": [1, 2] }, "cpu": 4, "memory": 5, "cpu": 6 }"#;
+
+        let mut d = serde_json::Deserializer::from_str(json);
+        let cpu = d
+            .deserialize_map(KeyedMapVisitor::new(CpuOnly(0), UnknownKeys::Fail))
+            .unwrap();
+        assert_eq!(10, cpu.0);
+    }
+
+    #[test]
+    fn visits_compact_catch_all() {
+        use std::collections::BTreeMap;
+
+        crate::keys!(Label {
+            Host("host"),
+            _ => Custom,
+        });
+
+        struct Labels(Vec<(Label, String)>);
+
+        impl<'de> KeyHandler<'de, Label> for Labels {
+            fn handle<A>(
+                &mut self,
+                key: Label,
+                value: &mut ValueAccess<'_, A>,
+            ) -> Result<(), A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                self.0.push((key, value.next_value()?));
+                Ok(())
+            }
+        }
+
+        let data = BTreeMap::from([("host", "a"), ("zone", "b")]);
+        let bytes = bincode::options().serialize(&data).unwrap();
+
+        let mut d = bincode::Deserializer::from_slice(&bytes, bincode::options());
+        let labels = d
+            .deserialize_map(KeyedMapVisitor::new(Labels(Vec::new()), UnknownKeys::Fail))
+            .unwrap();
+        assert_eq!(
+            vec![
+                (Label::Host, "a".to_owned()),
+                (Label::Custom("zone".into()), "b".to_owned()),
+            ],
+            labels.0
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use super::{KeySeed, Keys, UnknownKey};
+use serde::de;
+use std::{cell::RefCell, fmt, marker::PhantomData};
+
+thread_local! {
+    static PATH: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    static REPORTS: RefCell<Vec<Vec<RejectedKey>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Map of keys that skips unknown keys instead of failing, and reports them.
+///
+/// Rejections from `Lenient` maps nested in the values are also reported
+/// by the outer map. They are routed there through thread-local state, so
+/// nested values must be deserialized on the same thread as the outer map.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lenient<M> {
+    value: M,
+    rejected: Vec<RejectedKey>,
+}
+
+impl<M> Lenient<M> {
+    pub fn value(&self) -> &M {
+        &self.value
+    }
+
+    pub fn rejected(&self) -> &[RejectedKey] {
+        &self.rejected
+    }
+
+    pub fn into_parts(self) -> (M, Vec<RejectedKey>) {
+        (self.value, self.rejected)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RejectedKey {
+    path: String,
+    key: UnknownKey,
+}
+
+impl RejectedKey {
+    /// Dotted map keys from the outermost `Lenient` down to the rejected
+    /// key. Struct fields and sequence indices are not included, so the
+    /// path is relative to where that outermost map sits in the document.
+    pub fn key_path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn key(&self) -> &UnknownKey {
+        &self.key
+    }
+}
+
+impl fmt::Display for RejectedKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.key)
+    }
+}
+
+struct Report;
+
+impl Report {
+    fn open() -> Report {
+        REPORTS.with(|r| r.borrow_mut().push(Vec::new()));
+        Report
+    }
+
+    fn reject(key: UnknownKey) {
+        let path = PATH.with(|p| {
+            let p = p.borrow();
+            p.iter()
+                .map(String::as_str)
+                .chain([key.input()])
+                .collect::<Vec<_>>()
+                .join(".")
+        });
+
+        REPORTS.with(|r| {
+            if let Some(report) = r.borrow_mut().last_mut() {
+                report.push(RejectedKey { path, key });
+            }
+        });
+    }
+
+    fn close(self) -> Vec<RejectedKey> {
+        std::mem::forget(self);
+
+        REPORTS.with(|r| {
+            let mut reports = r.borrow_mut();
+            let rejected = reports.pop().unwrap_or_default();
+
+            if let Some(parent) = reports.last_mut() {
+                parent.extend(rejected.iter().cloned());
+            }
+
+            rejected
+        })
+    }
+}
+
+impl Drop for Report {
+    fn drop(&mut self) {
+        REPORTS.with(|r| r.borrow_mut().pop());
+    }
+}
+
+struct Segment;
+
+impl Segment {
+    fn enter(s: &str) -> Segment {
+        PATH.with(|p| p.borrow_mut().push(s.to_owned()));
+        Segment
+    }
+}
+
+impl Drop for Segment {
+    fn drop(&mut self) {
+        PATH.with(|p| p.borrow_mut().pop());
+    }
+}
+
+impl<'de, M, K, V> de::Deserialize<'de> for Lenient<M>
+where
+    M: Default + Extend<(K, V)> + IntoIterator<Item = (K, V)>,
+    K: Keys,
+    V: de::Deserialize<'de>,
+{
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        d.deserialize_map(Visitor(PhantomData))
+    }
+}
+
+struct Visitor<M, K, V>(PhantomData<(M, K, V)>);
+
+impl<'de, M, K, V> de::Visitor<'de> for Visitor<M, K, V>
+where
+    M: Default + Extend<(K, V)>,
+    K: Keys,
+    V: de::Deserialize<'de>,
+{
+    type Value = Lenient<M>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a map")
+    }
+
+    fn visit_map<A>(self, mut access: A) -> Result<Lenient<M>, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let report = Report::open();
+        let mut value = M::default();
+
+        while let Some(key) = access.next_key_seed(KeySeed::<K>(PhantomData))? {
+            match key {
+                Ok(k) => {
+                    let v = {
+                        let _segment = Segment::enter(k.as_str());
+                        access.next_value()?
+                    };
+
+                    value.extend([(k, v)]);
+                }
+                Err(unknown) => {
+                    access.next_value::<de::IgnoredAny>()?;
+                    Report::reject(unknown);
+                }
+            }
+        }
+
+        Ok(Lenient {
+            value,
+            rejected: report.close(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Lenient;
+    use std::collections::HashMap;
+
+    crate::keys!(Region {
+        Eu("eu"),
+        Us("us"),
+    });
+
+    crate::keys!(Color {
+        Red("red"),
+        Green("green"),
+    });
+
+    #[test]
+    fn collects_rejected_keys() {
+        let json = serde_json::json!({
+            "eu": { "red": 1, "purple": 2 },
+            "mars": { "red": 3 },
+            "us": { "gren": 4, "green": 5 },
+        });
+        let data: Lenient<HashMap<Region, Lenient<HashMap<Color, u8>>>> =
+            serde_json::from_value(json).unwrap();
+
+        let mut paths = data
+            .rejected()
+            .iter()
+            .map(|r| r.key_path())
+            .collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(vec!["eu.purple", "mars", "us.gren"], paths);
+
+        let us = &data.value()[&Region::Us];
+        assert_eq!(Some(&5), us.value().get(&Color::Green));
+        assert_eq!(
+            r#"us.gren: unknown variant "gren", did you mean "green"?"#,
+            us.rejected()[0].to_string()
+        );
+    }
+
+    #[test]
+    fn paths_are_relative_to_outermost_map() {
+        #[derive(serde::Deserialize)]
+        struct Config {
+            colors: Lenient<HashMap<Color, u8>>,
+        }
+
+        let json = serde_json::json!({ "colors": { "blu": 2 } });
+        let config: Config = serde_json::from_value(json).unwrap();
+
+        assert_eq!("blu", config.colors.rejected()[0].key_path());
+    }
+}
@@ -0,0 +1,255 @@
+use super::{KeyArray, Keys};
+use serde::{de, ser};
+use std::{fmt, marker::PhantomData};
+
+/// Map from keys to values, stored inline with one slot per key.
+pub struct KeyMap<K, V>
+where
+    K: Keys,
+{
+    slots: K::Array<Option<V>>,
+}
+
+impl<K, V> KeyMap<K, V>
+where
+    K: Keys,
+{
+    const SIZED: () = assert!(
+        <K::Array<Option<V>> as KeyArray<Option<V>>>::LEN == K::COUNT,
+        "Keys::Array must have one slot per key"
+    );
+
+    pub fn new() -> Self {
+        let () = Self::SIZED;
+
+        KeyMap {
+            slots: KeyArray::from_fn(|_| None),
+        }
+    }
+
+    pub fn get(&self, k: &K) -> Option<&V> {
+        self.slots.as_ref().get(k.index())?.as_ref()
+    }
+
+    pub fn get_mut(&mut self, k: &K) -> Option<&mut V> {
+        self.slots.as_mut().get_mut(k.index())?.as_mut()
+    }
+
+    pub fn contains_key(&self, k: &K) -> bool {
+        self.get(k).is_some()
+    }
+
+    /// Panics if `k` is a catch-all value, which has no slot.
+    pub fn insert(&mut self, k: K, v: V) -> Option<V> {
+        match self.slots.as_mut().get_mut(k.index()) {
+            Some(slot) => slot.replace(v),
+            None => panic!(r#"key "{}" has no slot in KeyMap"#, k.as_str()),
+        }
+    }
+
+    pub fn remove(&mut self, k: &K) -> Option<V> {
+        self.slots.as_mut().get_mut(k.index())?.take()
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.as_ref().iter().filter(|v| v.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.as_ref().iter().all(|v| v.is_none())
+    }
+
+    /// Iterates over the entries in declaration order.
+    pub fn iter(&self) -> impl Iterator<Item = (K, &V)> + '_ {
+        self.slots
+            .as_ref()
+            .iter()
+            .enumerate()
+            .filter_map(|(i, v)| Some((K::from_index(i)?, v.as_ref()?)))
+    }
+}
+
+impl<K, V> Default for KeyMap<K, V>
+where
+    K: Keys,
+{
+    fn default() -> Self {
+        KeyMap::new()
+    }
+}
+
+impl<K, V> Clone for KeyMap<K, V>
+where
+    K: Keys,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        KeyMap {
+            slots: KeyArray::from_fn(|i| self.slots.as_ref()[i].clone()),
+        }
+    }
+}
+
+impl<K, V> PartialEq for KeyMap<K, V>
+where
+    K: Keys,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.slots.as_ref() == other.slots.as_ref()
+    }
+}
+
+impl<K, V> fmt::Debug for KeyMap<K, V>
+where
+    K: Keys,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map()
+            .entries(self.iter().map(|(k, v)| (K::NAMES[k.index()], v)))
+            .finish()
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for KeyMap<K, V>
+where
+    K: Keys,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut map = KeyMap::new();
+
+        for (k, v) in iter {
+            map.insert(k, v);
+        }
+
+        map
+    }
+}
+
+impl<K, V> ser::Serialize for KeyMap<K, V>
+where
+    K: Keys + ser::Serialize,
+    V: ser::Serialize,
+{
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        use ser::SerializeMap;
+
+        let mut map = s.serialize_map(Some(self.len()))?;
+        for (k, v) in self.iter() {
+            map.serialize_entry(&k, v)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de, K, V> de::Deserialize<'de> for KeyMap<K, V>
+where
+    K: Keys + de::Deserialize<'de>,
+    V: de::Deserialize<'de>,
+{
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        d.deserialize_map(Visitor(PhantomData))
+    }
+}
+
+struct Visitor<K, V>(PhantomData<(K, V)>);
+
+impl<'de, K, V> de::Visitor<'de> for Visitor<K, V>
+where
+    K: Keys + de::Deserialize<'de>,
+    V: de::Deserialize<'de>,
+{
+    type Value = KeyMap<K, V>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a map")
+    }
+
+    fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut map = KeyMap::<K, V>::new();
+
+        while let Some(k) = access.next_key::<K>()? {
+            let slot = match map.slots.as_mut().get_mut(k.index()) {
+                Some(slot) => slot,
+                None => {
+                    return Err(de::Error::custom(format_args!(
+                        r#"key "{}" has no slot in KeyMap"#,
+                        k.as_str()
+                    )))
+                }
+            };
+
+            *slot = Some(access.next_value()?);
+        }
+
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KeyMap;
+
+    crate::keys!(Color {
+        Red("red"),
+        Green("green"),
+        Blue("blue"),
+    });
+
+    crate::keys!(Shape {
+        Circle("circle"),
+        _ => Other,
+    });
+
+    #[test]
+    fn insert_get() {
+        let mut map = KeyMap::new();
+
+        assert_eq!(None, map.insert(Color::Blue, 0));
+        assert_eq!(Some(0), map.insert(Color::Blue, 1));
+        map.insert(Color::Red, 2);
+
+        assert_eq!(Some(&1), map.get(&Color::Blue));
+        assert_eq!(None, map.get(&Color::Green));
+        assert_eq!(2, map.len());
+        assert_eq!(
+            vec![(Color::Red, &2), (Color::Blue, &1)],
+            map.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn roundtrip() {
+        let json = serde_json::json!({ "blue": 0, "red": 100 });
+        let map: KeyMap<Color, u8> = serde_json::from_value(json.clone()).unwrap();
+
+        assert_eq!(Some(&100), map.get(&Color::Red));
+        assert_eq!(json, serde_json::to_value(&map).unwrap());
+
+        let bytes = bincode::serialize(&map).unwrap();
+        assert_eq!(map, bincode::deserialize(&bytes).unwrap());
+    }
+
+    #[test]
+    fn rejects_catch_all() {
+        let json = serde_json::json!({ "circle": 0, "hexagon": 6 });
+        assert!(serde_json::from_value::<KeyMap<Shape, u8>>(json).is_err());
+        assert_eq!(
+            None,
+            KeyMap::<Shape, u8>::new().get(&Shape::Other("hexagon".into()))
+        );
+    }
+}
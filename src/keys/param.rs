@@ -0,0 +1,176 @@
+use super::UnknownKey;
+use serde::de;
+use std::{fmt, marker::PhantomData};
+
+/// Keys whose variants may carry a payload, written as `prefix<payload>suffix`
+/// such as `"retry:3"` or `"rgb(1,2,3)"`.
+///
+/// `Display` writes the key back in the form `parse` accepts. To keep that
+/// round trip exact, the derive rejects prefixes that overlap each other
+/// and plain names that fit a payload variant's prefix and suffix:
+///
+/// ```compile_fail
+/// #[derive(serde_types::keys::ParamKeys)]
+/// #[keys(rename_all = "lowercase")]
+/// enum Setting {
+///     Retry(u32),
+///     #[keys(rename = "retry:3")]
+///     Three,
+/// }
+/// ```
+pub trait ParamKeys: Sized + fmt::Display + 'static {
+    /// Plain names and payload patterns, used in error messages. A payload
+    /// is shown as `<payload>` unless the variant sets `#[keys(pattern)]`.
+    const PATTERNS: &'static [&'static str];
+
+    fn parse(s: &str) -> Option<Self>;
+}
+
+pub fn parse_param<K>(s: &str) -> Result<K, UnknownKey>
+where
+    K: ParamKeys,
+{
+    K::parse(s).ok_or_else(|| UnknownKey {
+        input: s.to_owned(),
+        expected: K::PATTERNS,
+    })
+}
+
+pub fn deserialize_param<'de, K, D>(d: D) -> Result<K, D::Error>
+where
+    K: ParamKeys,
+    D: de::Deserializer<'de>,
+{
+    d.deserialize_str(Visitor(PhantomData))
+}
+
+struct Visitor<K>(PhantomData<K>);
+
+impl<'de, K> de::Visitor<'de> for Visitor<K>
+where
+    K: ParamKeys,
+{
+    type Value = K;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        super::write_names(f, K::PATTERNS)
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<K, E>
+    where
+        E: de::Error,
+    {
+        parse_param(s).map_err(E::custom)
+    }
+
+    fn visit_bytes<E>(self, b: &[u8]) -> Result<K, E>
+    where
+        E: de::Error,
+    {
+        match std::str::from_utf8(b) {
+            Ok(s) => self.visit_str(s),
+            Err(_) => Err(E::invalid_value(de::Unexpected::Bytes(b), &self)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ParamKeys;
+    use std::{fmt, str::FromStr};
+
+    #[derive(Debug, PartialEq)]
+    struct Rgb(u8, u8, u8);
+
+    impl FromStr for Rgb {
+        type Err = ();
+
+        fn from_str(s: &str) -> Result<Rgb, ()> {
+            let mut parts = s.split(',').map(|p| p.trim().parse::<u8>().map_err(|_| ()));
+            let rgb = Rgb(
+                parts.next().ok_or(())??,
+                parts.next().ok_or(())??,
+                parts.next().ok_or(())??,
+            );
+
+            match parts.next() {
+                Some(_) => Err(()),
+                None => Ok(rgb),
+            }
+        }
+    }
+
+    impl fmt::Display for Rgb {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{},{},{}", self.0, self.1, self.2)
+        }
+    }
+
+    #[derive(Debug, PartialEq, crate::keys::ParamKeys)]
+    #[keys(crate = crate, rename_all = "lowercase")]
+    enum Setting {
+        #[keys(alias = "none")]
+        Off,
+        Retry(u32),
+        #[keys(prefix = "tz=")]
+        Timezone(String),
+        #[keys(prefix = "rgb(", suffix = ")", pattern = "rgb(<r>,<g>,<b>)")]
+        Color(Rgb),
+    }
+
+    #[test]
+    fn parses() {
+        assert_eq!(Some(Setting::Off), Setting::parse("none"));
+        assert_eq!(Some(Setting::Retry(3)), Setting::parse("retry:3"));
+        assert_eq!(
+            Some(Setting::Timezone("UTC".into())),
+            Setting::parse("tz=UTC")
+        );
+        assert_eq!(
+            Some(Setting::Color(Rgb(1, 2, 3))),
+            Setting::parse("rgb(1,2,3)")
+        );
+        assert_eq!(None, Setting::parse("retry:many"));
+        assert_eq!(None, Setting::parse("rgb(1,2,3"));
+        assert_eq!("rgb(1,2,3)", Setting::Color(Rgb(1, 2, 3)).to_string());
+    }
+
+    #[test]
+    fn raw_identifiers() {
+        #[derive(Debug, PartialEq, crate::keys::ParamKeys)]
+        #[keys(crate = crate, rename_all = "lowercase")]
+        enum Limit {
+            r#Loop(u32),
+            r#Break,
+        }
+
+        assert_eq!(Some(Limit::r#Loop(2)), Limit::parse("loop:2"));
+        assert_eq!(Some(Limit::r#Break), Limit::parse("break"));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let json = serde_json::json!(["off", "retry:3", "tz=UTC", "rgb(1,2,3)"]);
+        let settings: Vec<Setting> = serde_json::from_value(json.clone()).unwrap();
+
+        assert_eq!(Setting::Retry(3), settings[1]);
+        assert!(settings
+            .iter()
+            .all(|s| Setting::parse(&s.to_string()).as_ref() == Some(s)));
+        assert_eq!(json, serde_json::to_value(&settings).unwrap());
+    }
+
+    #[test]
+    fn errors() {
+        let err = "retry:x".parse::<Setting>().unwrap_err();
+        assert_eq!(
+            &["off", "retry:<payload>", "tz=<payload>", "rgb(<r>,<g>,<b>)"],
+            err.expected()
+        );
+
+        let err = serde_json::from_str::<Setting>(r#""ofF""#).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with(r#"unknown variant "ofF", did you mean "off"?"#));
+    }
+}
@@ -0,0 +1,347 @@
+use super::Keys;
+use serde::{de, ser};
+use std::{fmt, hash, marker::PhantomData};
+
+/// Set of keys, stored as a bitmask over their indices.
+pub struct KeySet<K>
+where
+    K: Keys,
+{
+    bits: u128,
+    _keys: PhantomData<K>,
+}
+
+impl<K> KeySet<K>
+where
+    K: Keys,
+{
+    const FITS: () = assert!(K::COUNT <= 128, "KeySet holds at most 128 keys");
+
+    pub fn new() -> Self {
+        let () = Self::FITS;
+
+        KeySet::from_bits(0)
+    }
+
+    fn from_bits(bits: u128) -> Self {
+        KeySet {
+            bits,
+            _keys: PhantomData,
+        }
+    }
+
+    fn bit(k: &K) -> Option<u128> {
+        (k.index() < K::COUNT).then(|| 1 << k.index())
+    }
+
+    pub fn contains(&self, k: &K) -> bool {
+        Self::bit(k).is_some_and(|bit| self.bits & bit != 0)
+    }
+
+    /// Panics if `k` is a catch-all value, which has no bit.
+    pub fn insert(&mut self, k: K) -> bool {
+        match Self::bit(&k) {
+            Some(bit) => {
+                let added = self.bits & bit == 0;
+                self.bits |= bit;
+                added
+            }
+            None => panic!(r#"key "{}" has no bit in KeySet"#, k.as_str()),
+        }
+    }
+
+    pub fn remove(&mut self, k: &K) -> bool {
+        let bit = match Self::bit(k) {
+            Some(bit) => bit,
+            None => return false,
+        };
+        let removed = self.bits & bit != 0;
+
+        self.bits &= !bit;
+        removed
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        KeySet::from_bits(self.bits | other.bits)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        KeySet::from_bits(self.bits & other.bits)
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        KeySet::from_bits(self.bits & !other.bits)
+    }
+
+    /// Iterates over the keys in declaration order.
+    pub fn iter(&self) -> impl Iterator<Item = K> + '_ {
+        (0..K::COUNT)
+            .filter(|i| self.bits & (1 << i) != 0)
+            .filter_map(K::from_index)
+    }
+
+    /// Deserializes from either a list of keys or a `{"key": true}` map.
+    pub fn deserialize_seq_or_map<'de, D>(d: D) -> Result<Self, D::Error>
+    where
+        K: de::Deserialize<'de>,
+        D: de::Deserializer<'de>,
+    {
+        d.deserialize_any(Visitor {
+            accepts_map: true,
+            _keys: PhantomData,
+        })
+    }
+}
+
+impl<K> Default for KeySet<K>
+where
+    K: Keys,
+{
+    fn default() -> Self {
+        KeySet::new()
+    }
+}
+
+impl<K> Clone for KeySet<K>
+where
+    K: Keys,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K> Copy for KeySet<K> where K: Keys {}
+
+impl<K> PartialEq for KeySet<K>
+where
+    K: Keys,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.bits == other.bits
+    }
+}
+
+impl<K> Eq for KeySet<K> where K: Keys {}
+
+impl<K> hash::Hash for KeySet<K>
+where
+    K: Keys,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: hash::Hasher,
+    {
+        self.bits.hash(state)
+    }
+}
+
+impl<K> fmt::Debug for KeySet<K>
+where
+    K: Keys,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set()
+            .entries(self.iter().map(|k| K::NAMES[k.index()]))
+            .finish()
+    }
+}
+
+impl<K> FromIterator<K> for KeySet<K>
+where
+    K: Keys,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+    {
+        let mut set = KeySet::new();
+
+        for k in iter {
+            set.insert(k);
+        }
+
+        set
+    }
+}
+
+impl<K> ser::Serialize for KeySet<K>
+where
+    K: Keys + ser::Serialize,
+{
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        use ser::SerializeSeq;
+
+        let mut seq = s.serialize_seq(Some(self.len()))?;
+        for k in self.iter() {
+            seq.serialize_element(&k)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de, K> de::Deserialize<'de> for KeySet<K>
+where
+    K: Keys + de::Deserialize<'de>,
+{
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        d.deserialize_seq(Visitor {
+            accepts_map: false,
+            _keys: PhantomData,
+        })
+    }
+}
+
+struct Visitor<K> {
+    accepts_map: bool,
+    _keys: PhantomData<K>,
+}
+
+impl<K> Visitor<K>
+where
+    K: Keys,
+{
+    fn insert<E>(set: &mut KeySet<K>, k: K) -> Result<(), E>
+    where
+        E: de::Error,
+    {
+        match KeySet::bit(&k) {
+            Some(bit) => {
+                set.bits |= bit;
+                Ok(())
+            }
+            None => Err(E::custom(format_args!(
+                r#"key "{}" has no bit in KeySet"#,
+                k.as_str()
+            ))),
+        }
+    }
+}
+
+impl<'de, K> de::Visitor<'de> for Visitor<K>
+where
+    K: Keys + de::Deserialize<'de>,
+{
+    type Value = KeySet<K>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.accepts_map {
+            true => write!(f, "a list of keys or a map of keys to booleans"),
+            false => write!(f, "a list of keys"),
+        }
+    }
+
+    fn visit_seq<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut set = KeySet::new();
+
+        while let Some(k) = access.next_element::<K>()? {
+            Self::insert(&mut set, k)?;
+        }
+
+        Ok(set)
+    }
+
+    fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        if !self.accepts_map {
+            return Err(de::Error::invalid_type(de::Unexpected::Map, &self));
+        }
+
+        let mut set = KeySet::new();
+
+        while let Some((k, enabled)) = access.next_entry::<K, bool>()? {
+            if enabled {
+                Self::insert(&mut set, k)?;
+            }
+        }
+
+        Ok(set)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KeySet;
+
+    crate::keys!(Scope {
+        Read("read"),
+        Write("write"),
+        Admin("admin"),
+    });
+
+    #[test]
+    fn operations() {
+        let a = [Scope::Admin, Scope::Read]
+            .into_iter()
+            .collect::<KeySet<_>>();
+        let b = [Scope::Read, Scope::Write]
+            .into_iter()
+            .collect::<KeySet<_>>();
+
+        assert!(a.contains(&Scope::Admin));
+        assert!(!a.contains(&Scope::Write));
+        assert_eq!(
+            vec![Scope::Read, Scope::Write, Scope::Admin],
+            a.union(&b).iter().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![Scope::Read],
+            a.intersection(&b).iter().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![Scope::Admin],
+            a.difference(&b).iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn roundtrip() {
+        let json = serde_json::json!(["admin", "read"]);
+        let set: KeySet<Scope> = serde_json::from_value(json).unwrap();
+
+        assert_eq!(2, set.len());
+        assert_eq!(
+            serde_json::json!(["read", "admin"]),
+            serde_json::to_value(set).unwrap()
+        );
+
+        let bytes = bincode::serialize(&set).unwrap();
+        assert_eq!(set, bincode::deserialize(&bytes).unwrap());
+    }
+
+    #[test]
+    fn deserializes_map() {
+        let json = serde_json::json!({ "admin": true, "write": false });
+        let set = KeySet::<Scope>::deserialize_seq_or_map(json).unwrap();
+
+        assert_eq!(vec![Scope::Admin], set.iter().collect::<Vec<_>>());
+        assert!(
+            serde_json::from_value::<KeySet<Scope>>(serde_json::json!({ "admin": true })).is_err()
+        );
+
+        let err = KeySet::<Scope>::deserialize_seq_or_map(serde_json::json!("admin")).unwrap_err();
+        assert_eq!(
+            r#"invalid type: string "admin", expected a list of keys or a map of keys to booleans"#,
+            err.to_string()
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use super::Keys;
+use serde::de::{self, value::MapAccessDeserializer};
+use std::{fmt, marker::PhantomData};
+
+/// Enum whose variant is chosen by a tag field holding a key.
+///
+/// The tag must be the first field of the map; the remaining fields are
+/// handed to [`Tagged::deserialize_content`] without buffering.
+pub trait Tagged<'de>: Sized {
+    type Tag: Keys + de::Deserialize<'de>;
+
+    const TAG: &'static str;
+
+    fn deserialize_content<D>(tag: Self::Tag, d: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>;
+}
+
+pub fn deserialize_tagged<'de, T, D>(d: D) -> Result<T, D::Error>
+where
+    T: Tagged<'de>,
+    D: de::Deserializer<'de>,
+{
+    d.deserialize_map(Visitor(PhantomData))
+}
+
+struct Visitor<T>(PhantomData<T>);
+
+impl<'de, T> de::Visitor<'de> for Visitor<T>
+where
+    T: Tagged<'de>,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, r#"a map starting with "{}""#, T::TAG)
+    }
+
+    fn visit_map<A>(self, mut access: A) -> Result<T, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        match access.next_key_seed(TagField(T::TAG))? {
+            Some(true) => {}
+            Some(false) => {
+                return Err(de::Error::custom(format_args!(
+                    r#"expected "{}" as the first field"#,
+                    T::TAG
+                )))
+            }
+            None => return Err(de::Error::missing_field(T::TAG)),
+        }
+
+        let tag = access.next_value::<T::Tag>()?;
+        T::deserialize_content(tag, MapAccessDeserializer::new(access))
+    }
+}
+
+struct TagField(&'static str);
+
+impl<'de> de::DeserializeSeed<'de> for TagField {
+    type Value = bool;
+
+    fn deserialize<D>(self, d: D) -> Result<bool, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        d.deserialize_identifier(self)
+    }
+}
+
+impl<'de> de::Visitor<'de> for TagField {
+    type Value = bool;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a field name")
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        Ok(s == self.0)
+    }
+
+    fn visit_bytes<E>(self, b: &[u8]) -> Result<bool, E>
+    where
+        E: de::Error,
+    {
+        Ok(b == self.0.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{deserialize_tagged, Tagged};
+    use serde::{de, Deserialize};
+
+    crate::keys!(Kind {
+        Ping("ping"),
+        Move("move"),
+    });
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Move {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Message {
+        Ping,
+        Move(Move),
+    }
+
+    impl<'de> Tagged<'de> for Message {
+        type Tag = Kind;
+
+        const TAG: &'static str = "type";
+
+        fn deserialize_content<D>(tag: Kind, d: D) -> Result<Self, D::Error>
+        where
+            D: de::Deserializer<'de>,
+        {
+            match tag {
+                Kind::Ping => de::IgnoredAny::deserialize(d).map(|_| Message::Ping),
+                Kind::Move => Move::deserialize(d).map(Message::Move),
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Message {
+        fn deserialize<D>(d: D) -> Result<Self, D::Error>
+        where
+            D: de::Deserializer<'de>,
+        {
+            deserialize_tagged(d)
+        }
+    }
+
+    #[test]
+    fn dispatches() {
+        let json = r#"[{ "type": "move", "x": 1, "y": 2 }, { "type": "ping" }]"#;
+
+        assert_eq!(
+            vec![Message::Move(Move { x: 1, y: 2 }), Message::Ping],
+            serde_json::from_str::<Vec<Message>>(json).unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_bad_tags() {
+        let err = serde_json::from_str::<Message>(r#"{ "type": "jump" }"#).unwrap_err();
+        assert!(err.to_string().starts_with(r#"unknown variant "jump""#));
+
+        let err = serde_json::from_str::<Message>(r#"{ "x": 1, "type": "move" }"#).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with(r#"expected "type" as the first field"#));
+
+        let err = serde_json::from_str::<Message>("{}").unwrap_err();
+        assert!(err.to_string().starts_with("missing field `type`"));
+    }
+}
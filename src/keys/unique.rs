@@ -0,0 +1,99 @@
+use super::Keys;
+use serde::de;
+use std::{collections::HashSet, fmt, marker::PhantomData};
+
+/// Deserializes a map of keys, failing on the first duplicated key instead
+/// of keeping the last value.
+///
+/// Meant for `#[serde(deserialize_with = "...")]` on `HashMap<K, V>` or
+/// `BTreeMap<K, V>` fields.
+pub fn deserialize_unique<'de, D, M, K, V>(d: D) -> Result<M, D::Error>
+where
+    D: de::Deserializer<'de>,
+    M: Default + Extend<(K, V)> + IntoIterator<Item = (K, V)>,
+    K: Keys + de::Deserialize<'de>,
+    V: de::Deserialize<'de>,
+{
+    d.deserialize_map(Visitor(PhantomData))
+}
+
+struct Visitor<M, K, V>(PhantomData<(M, K, V)>);
+
+impl<'de, M, K, V> de::Visitor<'de> for Visitor<M, K, V>
+where
+    M: Default + Extend<(K, V)>,
+    K: Keys + de::Deserialize<'de>,
+    V: de::Deserialize<'de>,
+{
+    type Value = M;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a map")
+    }
+
+    fn visit_map<A>(self, mut access: A) -> Result<M, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut map = M::default();
+        let mut seen = vec![false; K::COUNT];
+        let mut seen_other = HashSet::new();
+
+        while let Some(k) = access.next_key::<K>()? {
+            let first = match seen.get_mut(k.index()) {
+                Some(seen) => !std::mem::replace(seen, true),
+                None => seen_other.insert(k.as_str().to_owned()),
+            };
+
+            if !first {
+                return Err(de::Error::custom(format_args!(
+                    r#"duplicate key "{}""#,
+                    k.as_str()
+                )));
+            }
+
+            let v = access.next_value()?;
+            map.extend([(k, v)]);
+        }
+
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use std::collections::HashMap;
+
+    crate::keys!(Color {
+        Red("red"),
+        Green("green"),
+        _ => Other,
+    });
+
+    #[derive(Deserialize)]
+    struct Config {
+        #[serde(deserialize_with = "super::deserialize_unique")]
+        colors: HashMap<Color, u8>,
+    }
+
+    #[test]
+    fn accepts_unique() {
+        let json = r#"{ "colors": { "red": 1, "green": 2, "blue": 3 } }"#;
+        let config = serde_json::from_str::<Config>(json).unwrap();
+
+        assert_eq!(Some(&3), config.colors.get(&Color::Other("blue".into())));
+    }
+
+    #[test]
+    fn rejects_duplicates() {
+        let json = r#"{ "colors": { "red": 1, "green": 2, "red": 3 } }"#;
+        let err = serde_json::from_str::<Config>(json).err().unwrap();
+        assert!(err.to_string().starts_with(r#"duplicate key "red""#));
+
+        let json = r#"{ "blue": 1, "blue": 2 }"#;
+        let mut d = serde_json::Deserializer::from_str(json);
+        let err = super::deserialize_unique::<_, HashMap<Color, u8>, _, _>(&mut d).unwrap_err();
+        assert!(err.to_string().starts_with(r#"duplicate key "blue""#));
+    }
+}